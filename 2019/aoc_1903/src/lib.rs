@@ -1,3 +1,4 @@
+use arbgrid::{Coord2D, Direction};
 use std::convert::TryInto;
 /*
 --- Day 3: Crossed Wires ---
@@ -67,37 +68,13 @@ enum LineDir {
     Vert,
 }
 
-#[allow(non_snake_case)]
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-pub enum Dir {
-    U,
-    D,
-    L,
-    R,
-}
-
-impl Dir {
-    fn from_char(c: char) -> Result<Dir, ()> {
-        match c {
-            'U' => Ok(Dir::U),
-            'D' => Ok(Dir::D),
-            'L' => Ok(Dir::L),
-            'R' => Ok(Dir::R),
-            _ => Err(()),
-        }
-    }
-}
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Point(i32, i32);
 
 impl Point {
-    pub fn go(self: Point, dir: Dir, dist: u32) -> Point {
-        match dir {
-            Dir::U => Point(self.0, self.1 + dist as i32),
-            Dir::D => Point(self.0, self.1 - dist as i32),
-            Dir::R => Point(self.0 + dist as i32, self.1),
-            Dir::L => Point(self.0 - dist as i32, self.1),
-        }
+    pub fn go(self: Point, dir: Direction, dist: u32) -> Point {
+        let Coord2D(dx, dy) = dir.offset();
+        Point(self.0 + dx * dist as i32, self.1 + dy * dist as i32)
     }
 
     pub fn distance(self) -> u32 {
@@ -109,37 +86,38 @@ impl Point {
 struct Segment {
     origin: Point,
     direction: LineDir,
-    orig_dir: Dir,
+    orig_dir: Direction,
     length: u32,
 }
 
 impl Segment {
-    fn from_dir(p: Point, dir: Dir, dist: u32) -> Segment {
+    fn from_dir(p: Point, dir: Direction, dist: u32) -> Segment {
         match dir {
-            Dir::U => Segment {
+            Direction::Up => Segment {
                 origin: p,
                 direction: LineDir::Vert,
                 orig_dir: dir,
                 length: dist,
             },
-            Dir::R => Segment {
+            Direction::Right => Segment {
                 origin: p,
                 direction: LineDir::Hor,
                 orig_dir: dir,
                 length: dist,
             },
-            Dir::D => Segment {
-                origin: p.go(Dir::D, dist),
+            Direction::Down => Segment {
+                origin: p.go(Direction::Down, dist),
                 direction: LineDir::Vert,
                 orig_dir: dir,
                 length: dist,
             },
-            Dir::L => Segment {
-                origin: p.go(Dir::L, dist),
+            Direction::Left => Segment {
+                origin: p.go(Direction::Left, dist),
                 direction: LineDir::Hor,
                 orig_dir: dir,
                 length: dist,
             },
+            _ => panic!("Wires don't go diagonally"),
         }
     }
 
@@ -196,10 +174,11 @@ impl Segment {
         }
 
         let distance: u32 = match self.orig_dir {
-            Dir::U => (point.1 - self.origin.1) as u32,
-            Dir::D => self.length - ((point.1 - self.origin.1) as u32),
-            Dir::R => (point.0 - self.origin.0) as u32,
-            Dir::L => self.length - ((point.0 - self.origin.0) as u32),
+            Direction::Up => (point.1 - self.origin.1) as u32,
+            Direction::Down => self.length - ((point.1 - self.origin.1) as u32),
+            Direction::Right => (point.0 - self.origin.0) as u32,
+            Direction::Left => self.length - ((point.0 - self.origin.0) as u32),
+            _ => unreachable!(),
         };
         Some(distance)
     }
@@ -212,11 +191,11 @@ struct Line {
 
 impl Line {
     fn from_str(s: &str) -> Result<Line, ()> {
-        let path: Result<Vec<(Dir, u32)>, ()> = s
+        let path: Result<Vec<(Direction, u32)>, ()> = s
             .split(',')
-            .map(|s| -> Result<(Dir, u32), ()> {
+            .map(|s| -> Result<(Direction, u32), ()> {
                 let (s_dir, s_len) = s.split_at(1);
-                let dir = Dir::from_char(s_dir.chars().next().unwrap()).unwrap();
+                let dir = Direction::from_char(s_dir.chars().next().unwrap()).ok_or(())?;
                 let len: u32 = s_len.parse().unwrap();
                 Ok((dir, len))
             })
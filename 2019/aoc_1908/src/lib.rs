@@ -1,5 +1,8 @@
 #![allow(dead_code)]
+use arbgrid::{ArbGrid, Coord2D, ImageOptions, Orientation, Rgb};
 use array2d::Array2D;
+use std::io;
+use std::path::Path;
 
 pub fn str_to_image(input: &str, w: usize, h: usize) -> Vec<Array2D<u8>> {
     let base_array: Vec<u8> = input
@@ -79,6 +82,41 @@ fn flatten(img: &[Array2D<u8>]) -> Array2D<u8> {
     out
 }
 
+/// The final picture for an input, after stacking the layers
+pub fn decode(input: &str, w: usize, h: usize) -> Array2D<u8> {
+    flatten(&str_to_image(input, w, h))
+}
+
+/// One layer as a grid, top-down like `from_str_ogl`. Every pixel gets
+/// stored so the bounds are the whole layer, but black is the default, so
+/// a decoded picture can go straight to `aococr::read`.
+pub fn layer_to_grid(layer: &Array2D<u8>) -> ArbGrid<u8> {
+    let mut grid = ArbGrid::new(0);
+    for y in 0..layer.num_rows() {
+        for x in 0..layer.num_columns() {
+            grid.insert(Coord2D(x as i32, y as i32), layer[(y, x)]);
+        }
+    }
+    grid
+}
+
+fn pixel_color(pixel: &u8, _xy: Coord2D) -> Rgb {
+    match pixel {
+        0 => [0, 0, 0],
+        1 => [255, 255, 255],
+        _ => [255, 0, 255], // Still transparent, so make it stand out
+    }
+}
+
+/// Saves a layer as an image, picking the format from the extension
+pub fn save_layer(layer: &Array2D<u8>, path: impl AsRef<Path>, scale: usize) -> io::Result<()> {
+    layer_to_grid(layer).save_image(
+        path,
+        ImageOptions::new(scale, Orientation::YDown),
+        &pixel_color,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,6 +154,12 @@ mod tests {
         let img = str_to_image(in_str, 25, 6);
         let out = flatten(&img);
         assert_eq!(layer_to_str(&out), "011001111010010100100110010010100001010010010100101000011100110001001010010100001000010100100101111010010100001010010010100100110011110100100110010010");
-        // It's 'CEKUA', but I'm not implementing image recognition :/
+        assert_eq!(aococr::read(&out), Ok("CEKUA".to_string()));
+        assert_eq!(decode(in_str, 25, 6), out);
+        let grid = layer_to_grid(&out);
+        assert_eq!(grid.bounds(), Some((Coord2D(0, 0), Coord2D(24, 5))));
+        assert_eq!(*grid.get(Coord2D(1, 0)), 1);
+        assert_eq!(*grid.get(Coord2D(0, 0)), 0);
+        assert_eq!(aococr::read(&grid), Ok("CEKUA".to_string()));
     }
 }
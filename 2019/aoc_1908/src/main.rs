@@ -0,0 +1,17 @@
+use aoc_1908::{decode, save_layer};
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 3 {
+        eprintln!("Usage: {} <input> <image.png|.ppm|.svg>", args[0]);
+        std::process::exit(1);
+    }
+
+    let input = std::fs::read_to_string(&args[1]).expect("Couldn't read input");
+    let image = decode(&input, 25, 6);
+    save_layer(&image, &args[2], 10).expect("Couldn't save image");
+    match aococr::read(&image) {
+        Ok(text) => println!("{}", text),
+        Err(e) => eprintln!("Saved, but couldn't read it: {}", e),
+    }
+}
@@ -0,0 +1,25 @@
+use aoc_1910::{best_station, AstGrid, GridSquare};
+use arbgrid::{Coord2D, DenseGrid};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn parse() -> AstGrid {
+    AstGrid::from_str_ogl(
+        Coord2D(0, 0),
+        include_str!("../src/test_input.txt"),
+        &|c, _xy| Some(GridSquare::from_char(c)),
+    )
+}
+
+/// Same lookup-heavy search on each backing, so the only difference is `get`
+fn bench_backings(c: &mut Criterion) {
+    let sparse = parse();
+    let dense = DenseGrid::from(&sparse);
+    let mut group = c.benchmark_group("best station");
+    group.sample_size(10);
+    group.bench_function("ArbGrid", |b| b.iter(|| best_station(black_box(&sparse))));
+    group.bench_function("DenseGrid", |b| b.iter(|| best_station(black_box(&dense))));
+    group.finish();
+}
+
+criterion_group!(benches, bench_backings);
+criterion_main!(benches);
@@ -1,24 +1,9 @@
-use arbgrid::{ArbGrid, Coord2D};
-use std::cmp::{max, min, PartialEq};
-use std::ops::Rem;
+use arbgrid::{points_between, sweep, visible_from, ArbGrid, Coord2D, Grid};
+use std::cmp::PartialEq;
 
-/// Euclid's two-thousand-year-old algorithm for finding the greatest common
-/// divisor.
-/// Taken from the rust standard library docs :)
-/// ```
-/// assert_eq!(aoc_1910::gcd(1, 10), 1);
-/// assert_eq!(aoc_1910::gcd(2, 10), 2);
-/// ```
-pub fn gcd<T: PartialEq + Rem<Output = T> + Default + Copy>(x: T, y: T) -> T {
-    let mut x = x;
-    let mut y = y;
-    while y != T::default() {
-        let t = y;
-        y = x % y;
-        x = t;
-    }
-    x
-}
+pub use arbgrid::Heading;
+
+pub use aocmath::gcd;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum GridSquare {
@@ -75,43 +60,24 @@ impl GridSquare {
 /// assert_eq!(visible_points(Coord2D(0,0), Coord2D(1,5)).len(), 0);
 /// ```
 pub fn visible_points(this: Coord2D, other: Coord2D) -> Vec<Coord2D> {
-    let min_x = min(other.0, this.0);
-    let max_x = max(other.0, this.0);
-    let min_y = min(other.1, this.1);
-    let max_y = max(other.1, this.1);
-    let delta = Coord2D(other.0 - this.0, other.1 - this.1);
-    let delta_gcd = gcd(delta.0.abs(), delta.1.abs());
-    if delta.0 == 0 {
-        // Case 1: Points are in a vertical line
-        return (min_y + 1..max_y).map(|y| Coord2D(this.0, y)).collect();
-    } else if delta.1 == 0 {
-        // Case 2: Points are in a horizontal line
-        return (min_x + 1..max_x).map(|x| Coord2D(x, this.1)).collect();
-    } else if delta_gcd == 1 {
-        // Case 3: Points are diagonal and no point exists between
-        return vec![];
-    } else {
-        // Case 4: Diagonal and some points between
-        let delta_unit = Coord2D(delta.0 / delta_gcd, delta.1 / delta_gcd);
-        return (1..delta_gcd)
-            .map(|m| Coord2D(this.0 + delta_unit.0 * m, this.1 + delta_unit.1 * m))
-            .collect();
-    }
-    // Rust: "warning: unreachable statement"
-    // Me: LEARN TO READ!!!! THIS IS YOUR MACRO!!! WTF!!!
-    #[allow(unreachable_code)]
-    {
-        unreachable!();
-    }
+    points_between(this, other).collect()
 }
 
-pub fn do_10a(input: &str) -> (Coord2D, usize) {
+fn parse(input: &str) -> AstGrid {
     let mut grid = AstGrid::from_str_ogl(Coord2D(0, 0), input, &|c, _xy| {
         Some(GridSquare::from_char(c))
     });
     grid.trim();
+    grid
+}
+
+pub fn do_10a(input: &str) -> (Coord2D, usize) {
+    most_visible(&parse(input))
+}
 
-    let filled_grid = clone_and_fill(&grid);
+/// The asteroid that can see the most others, and how many it sees
+fn most_visible(grid: &AstGrid) -> (Coord2D, usize) {
+    let filled_grid = clone_and_fill(grid);
 
     filled_grid
         .iter()
@@ -137,25 +103,97 @@ pub fn clone_and_clear(other: &AstGrid) -> AstGrid {
     new_grid
 }
 
+fn asteroids(grid: &AstGrid) -> impl Iterator<Item = Coord2D> + '_ {
+    grid.iter()
+        .filter(|(_, sq)| **sq != GridSquare::Empty)
+        .map(|(xy, _)| *xy)
+}
+
+/// Counts what each asteroid can see. Asteroids are visible exactly when
+/// they're the closest along their heading, so the count is just the number
+/// of distinct headings, and we never need to look at the cells in between.
+///
+/// Each asteroid's count is a sort of all the others, so O(n log n), and
+/// doing that for all n of them makes the whole fill O(n² log n).
 pub fn clone_and_fill(grid: &AstGrid) -> AstGrid {
     let mut filled_grid = grid.clone();
+    let all: Vec<Coord2D> = asteroids(grid).collect();
 
     for (square_xy, square) in filled_grid.iter_mut() {
-        let count = grid
-            .iter()
-            .map(|(other_xy, _)| {
-                visible_points(*square_xy, *other_xy)
-                    .iter()
-                    .all(|xy| *grid.get(*xy) == GridSquare::Empty)
-                    && other_xy != square_xy
-            })
-            .filter(|b| *b)
-            .count();
-        *square = GridSquare::Known(count);
+        if *square == GridSquare::Empty {
+            continue;
+        }
+        let visible = visible_from(*square_xy, all.iter().copied());
+        *square = GridSquare::Known(visible.len());
     }
     filled_grid
 }
 
+/// Every other asteroid in the order a laser at `station` destroys them. It
+/// starts pointing up and turns clockwise, taking out only the closest
+/// asteroid on each heading per turn.
+pub fn vaporize_order(grid: &AstGrid, station: Coord2D) -> Vec<Coord2D> {
+    // Up is -y on these grids, and turning clockwise on screen is
+    // counterclockwise in raw coordinates
+    let by_heading = sweep(station, asteroids(grid), Heading::new(0, -1).unwrap());
+
+    let mut order = Vec::new();
+    let mut rotation = 0;
+    loop {
+        let before = order.len();
+        for (_, line) in by_heading.iter() {
+            if let Some(xy) = line.get(rotation) {
+                order.push(*xy);
+            }
+        }
+        if order.len() == before {
+            break;
+        }
+        rotation += 1;
+    }
+    order
+}
+
+/// Where the 200th asteroid goes, from the best spot, as `x * 100 + y`
+pub fn do_10b(input: &str) -> Option<i32> {
+    let grid = parse(input);
+    let (station, _) = most_visible(&grid);
+    let target = vaporize_order(&grid, station).get(199).copied()?;
+    Some(target.0 * 100 + target.1)
+}
+
+/// Every asteroid, on any backing
+fn asteroids_in<G: Grid<GridSquare>>(grid: &G) -> Vec<Coord2D> {
+    grid.cells()
+        .filter(|(_, sq)| **sq != GridSquare::Empty)
+        .map(|(xy, _)| xy)
+        .collect()
+}
+
+/// How many of `asteroids` `xy` can see, checking every cell in between.
+/// Slow, but it's nothing but lookups, so it works the same on any backing.
+fn count_visible<G: Grid<GridSquare>>(grid: &G, asteroids: &[Coord2D], xy: Coord2D) -> usize {
+    asteroids
+        .iter()
+        .filter(|other| {
+            **other != xy
+                && visible_points(xy, **other)
+                    .iter()
+                    .all(|between| *grid.get(*between) == GridSquare::Empty)
+        })
+        .count()
+}
+
+/// `do_10a` by brute force, looking at what's between every pair
+pub fn best_station<G: Grid<GridSquare>>(grid: &G) -> (Coord2D, usize) {
+    let asteroids = asteroids_in(grid);
+    asteroids
+        .iter()
+        .map(|xy| (*xy, count_visible(grid, &asteroids, *xy)))
+        .max_by_key(|(_xy, count)| *count)
+        .unwrap()
+}
+
 pub fn show_visible(grid: &AstGrid, xy: Coord2D) -> AstGrid {
     let mut filled_grid = AstGrid::new(GridSquare::Empty);
 
@@ -185,6 +223,7 @@ pub fn show_visible(grid: &AstGrid, xy: Coord2D) -> AstGrid {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use arbgrid::DenseGrid;
     const SAMPLE_GRID_1_FILLED: &str = "\
 .7..7
 .....
@@ -296,6 +335,105 @@ mod tests {
     fn test_sg5() {
         assert_eq!(do_10a(SAMPLE_GRID_5), (Coord2D(11, 13), 210));
     }
+    #[test]
+    fn heading_order() {
+        let h = |dx, dy| Heading::new(dx, dy).unwrap();
+        // Clockwise from up, with a y-down grid
+        let mut headings = vec![
+            h(-1, -1),
+            h(-1, 0),
+            h(-1, 1),
+            h(0, 1),
+            h(1, 1),
+            h(1, 0),
+            h(2, -1),
+            h(1, -2),
+            h(0, -1),
+        ];
+        headings.reverse();
+        headings.sort_by(|a, b| a.cmp_from(*b, h(0, -1)));
+        assert_eq!(
+            headings,
+            vec!(
+                h(0, -1),
+                h(1, -2),
+                h(2, -1),
+                h(1, 0),
+                h(1, 1),
+                h(0, 1),
+                h(-1, 1),
+                h(-1, 0),
+                h(-1, -1),
+            )
+        );
+    }
+
+    #[test]
+    fn test_vaporize_small() {
+        let input = "\
+.#....#####...#..
+##...##.#####..##
+##...#...#.#####.
+..#.....#...###..
+..#.#.....#....##";
+        let grid = AstGrid::from_str_ogl(Coord2D(0, 0), input, &|c, _xy| {
+            Some(GridSquare::from_char(c))
+        });
+        let order = vaporize_order(&grid, Coord2D(8, 3));
+        assert_eq!(
+            &order[..9],
+            &[
+                Coord2D(8, 1),
+                Coord2D(9, 0),
+                Coord2D(9, 1),
+                Coord2D(10, 0),
+                Coord2D(9, 2),
+                Coord2D(11, 1),
+                Coord2D(12, 1),
+                Coord2D(11, 2),
+                Coord2D(15, 1)
+            ]
+        );
+        assert_eq!(
+            &order[9..18],
+            &[
+                Coord2D(12, 2),
+                Coord2D(13, 2),
+                Coord2D(14, 2),
+                Coord2D(15, 2),
+                Coord2D(12, 3),
+                Coord2D(16, 4),
+                Coord2D(15, 4),
+                Coord2D(10, 4),
+                Coord2D(4, 4)
+            ]
+        );
+        assert_eq!(order.len(), 36);
+        assert_eq!(order.last(), Some(&Coord2D(14, 3)));
+    }
+
+    #[test]
+    fn test_vaporize_sg5() {
+        let mut grid = AstGrid::from_str_ogl(Coord2D(0, 0), SAMPLE_GRID_5, &|c, _xy| {
+            Some(GridSquare::from_char(c))
+        });
+        grid.trim();
+        let order = vaporize_order(&grid, Coord2D(11, 13));
+        assert_eq!(order.len(), 299);
+        assert_eq!(order[0], Coord2D(11, 12));
+        assert_eq!(order[1], Coord2D(12, 1));
+        assert_eq!(order[2], Coord2D(12, 2));
+        assert_eq!(order[9], Coord2D(12, 8));
+        assert_eq!(order[19], Coord2D(16, 0));
+        assert_eq!(order[49], Coord2D(16, 9));
+        assert_eq!(order[99], Coord2D(10, 16));
+        assert_eq!(order[198], Coord2D(9, 6));
+        assert_eq!(order[199], Coord2D(8, 2));
+        assert_eq!(order[200], Coord2D(10, 9));
+        assert_eq!(order[298], Coord2D(11, 1));
+        assert_eq!(do_10b(SAMPLE_GRID_5), Some(802));
+    }
+
     #[test]
     fn test_1a() {
         assert_eq!(
@@ -303,4 +441,14 @@ mod tests {
             (Coord2D(37, 25), 309)
         );
     }
+
+    #[test]
+    fn either_backing() {
+        let grid = AstGrid::from_str_ogl(Coord2D(0, 0), SAMPLE_GRID_4, &|c, _xy| {
+            Some(GridSquare::from_char(c))
+        });
+        let dense = DenseGrid::from(&grid);
+        assert_eq!(best_station(&grid), (Coord2D(6, 3), 41));
+        assert_eq!(best_station(&dense), (Coord2D(6, 3), 41));
+    }
 }
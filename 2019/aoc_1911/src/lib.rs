@@ -1,4 +1,6 @@
-use arbgrid::{ArbGrid, Coord2D};
+use arbgrid::{ArbGrid, Coord2D, Direction, ImageOptions, Orientation};
+use std::io;
+use std::path::Path;
 
 use intcode::{Atom, IntMachine, RunMode};
 
@@ -31,35 +33,17 @@ impl Default for Color {
     }
 }
 
-#[derive(Clone, Copy)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Direction {
-    fn turn_cw(self) -> Self {
-        match self {
-            Direction::Up => Direction::Right,
-            Direction::Right => Direction::Down,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-        }
-    }
-
-    fn turn_ccw(self) -> Self {
-        match self {
-            Direction::Up => Direction::Left,
-            Direction::Right => Direction::Up,
-            Direction::Down => Direction::Right,
-            Direction::Left => Direction::Down,
-        }
-    }
+pub fn run_gridpaint(grid: &mut ArbGrid<Color>, input: &[Atom]) {
+    run_gridpaint_watched(grid, input, &mut |_grid, _robot| Ok(())).expect("nothing to fail");
 }
 
-pub fn run_gridpaint(grid: &mut ArbGrid<Color>, input: &[Atom]) {
+/// Same as `run_gridpaint`, but calls `watch` with the hull and where the
+/// robot is after every move, e.g. to animate it
+pub fn run_gridpaint_watched(
+    grid: &mut ArbGrid<Color>,
+    input: &[Atom],
+    watch: &mut dyn FnMut(&ArbGrid<Color>, Coord2D) -> io::Result<()>,
+) -> io::Result<()> {
     let mut cpu = IntMachine::new(input.to_vec());
     let mut location: Coord2D = Coord2D(0, 0);
     let mut direction = Direction::Up;
@@ -78,12 +62,9 @@ pub fn run_gridpaint(grid: &mut ArbGrid<Color>, input: &[Atom]) {
             _ => panic!(),
         };
 
-        location = match direction {
-            Direction::Up => location.up(1),
-            Direction::Down => location.down(1),
-            Direction::Left => location.left(1),
-            Direction::Right => location.right(1),
-        };
+        location = location.step(direction, 1);
+
+        watch(grid, location)?;
 
         match halt {
             RunMode::EndPgm => break,
@@ -91,6 +72,7 @@ pub fn run_gridpaint(grid: &mut ArbGrid<Color>, input: &[Atom]) {
             RunMode::InputStalled => continue,
         };
     }
+    Ok(())
 }
 
 pub fn do_aoc1911(input: &[Atom]) -> usize {
@@ -114,6 +96,18 @@ pub fn do_aoc1911_b(input: &[Atom]) -> ArbGrid<Color> {
     grid
 }
 
+/// Saves the hull as an image, picking the format from the extension
+pub fn save_hull(grid: &ArbGrid<Color>, path: impl AsRef<Path>, scale: usize) -> io::Result<()> {
+    grid.save_image(
+        path,
+        ImageOptions::new(scale, Orientation::YUp),
+        &|color, _coord| match color {
+            Color::Black => [0, 0, 0],
+            Color::White => [255, 255, 255],
+        },
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,6 +154,8 @@ mod tests {
         );
 
         let mut calc_grid = do_aoc1911_b(&in_nums.unwrap());
+        // The black panels it painted don't count, trimmed or not
+        assert_eq!(calc_grid, expected_grid);
         calc_grid.trim();
         println!("ACTUAL:");
         println!("{:?}", calc_grid.bounds());
@@ -170,6 +166,9 @@ mod tests {
                 Color::White => '#',
             })
         );
-        assert_eq!(calc_grid, expected_grid);
+        assert_eq!(
+            aococr::read(&aococr::FlipY(&calc_grid)),
+            Ok("GREJALPR".to_string())
+        );
     }
 }
@@ -0,0 +1,65 @@
+use aoc_1911::{do_aoc1911_b, run_gridpaint_watched, save_hull, Color};
+use arbgrid::{ArbGrid, Asciicast, Coord2D, Renderer, Style};
+use intcode::Atom;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::Duration;
+
+const FPS: u32 = 60;
+
+fn hull_style(color: &Color, xy: Coord2D, robot: Coord2D) -> Style {
+    let style = match color {
+        Color::Black => Style::plain(' '),
+        Color::White => Style::plain('#'),
+    };
+    if xy == robot {
+        style.bg([255, 64, 64])
+    } else {
+        style
+    }
+}
+
+/// Paints the hull, showing each step on `out`
+fn animate(program: &[Atom], screen: Renderer<impl Write>) -> io::Result<ArbGrid<Color>> {
+    let mut screen = screen;
+    let mut hull = ArbGrid::new(Color::Black);
+    *hull.get_mut(Coord2D(0, 0)) = Color::White;
+    run_gridpaint_watched(&mut hull, program, &mut |grid, robot| {
+        screen.draw(grid, &|color, xy| hull_style(color, xy, robot))
+    })?;
+    screen.finish()?;
+    Ok(hull)
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 3 {
+        eprintln!(
+            "Usage: {} <program> <image.png|.ppm|.svg> [--watch | --record <out.cast>]",
+            args[0]
+        );
+        std::process::exit(1);
+    }
+
+    let program: Vec<Atom> = std::fs::read_to_string(&args[1])
+        .expect("Couldn't read program")
+        .lines()
+        .collect::<String>()
+        .split(',')
+        .map(|s| s.trim().parse::<Atom>().expect("Bad program"))
+        .collect();
+
+    let mut hull = match args.get(3).map(|s| s.as_str()) {
+        Some("--watch") => animate(&program, Renderer::new(io::stdout()).max_fps(FPS)),
+        Some("--record") => {
+            let path = args.get(4).expect("Where should the recording go?");
+            let file = BufWriter::new(File::create(path).expect("Couldn't create recording"));
+            Asciicast::new(file, 80, 24, Duration::from_secs(1) / FPS)
+                .and_then(|cast| animate(&program, Renderer::new(cast)))
+        }
+        _ => Ok(do_aoc1911_b(&program)),
+    }
+    .expect("Couldn't draw");
+    hull.trim();
+    save_hull(&hull, &args[2], 10).expect("Couldn't save image");
+}
@@ -0,0 +1,180 @@
+use aocmath::{lcm, Vec3};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Moon {
+    pub pos: Vec3,
+    pub vel: Vec3,
+}
+
+impl FromStr for Moon {
+    type Err = ();
+    /// Parses a moon at rest from `<x=-1, y=0, z=2>`
+    fn from_str(s: &str) -> Result<Moon, ()> {
+        let coords: Option<Vec<i64>> = s
+            .trim()
+            .strip_prefix('<')
+            .and_then(|s| s.strip_suffix('>'))
+            .ok_or(())?
+            .split(',')
+            .map(|part| part.split('=').nth(1)?.trim().parse().ok())
+            .collect();
+        match coords.ok_or(())?.as_slice() {
+            [x, y, z] => Ok(Moon {
+                pos: Vec3(*x, *y, *z),
+                vel: Vec3::ZERO,
+            }),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Moon {
+    pub fn energy(&self) -> i64 {
+        self.pos.manhattan() * self.vel.manhattan()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct System {
+    pub moons: Vec<Moon>,
+}
+
+impl FromStr for System {
+    type Err = ();
+    fn from_str(s: &str) -> Result<System, ()> {
+        let moons: Result<Vec<Moon>, ()> = s
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(Moon::from_str)
+            .collect();
+        Ok(System { moons: moons? })
+    }
+}
+
+impl System {
+    pub fn step(&mut self) {
+        for i in 0..self.moons.len() {
+            for j in (i + 1)..self.moons.len() {
+                let pull = (self.moons[j].pos - self.moons[i].pos).signum();
+                self.moons[i].vel += pull;
+                self.moons[j].vel -= pull;
+            }
+        }
+        for moon in self.moons.iter_mut() {
+            moon.pos += moon.vel;
+        }
+    }
+
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    pub fn energy(&self) -> i64 {
+        self.moons.iter().map(Moon::energy).sum()
+    }
+
+    /// How long until a single axis comes back around to where it started.
+    /// Every step can be undone, so the first repeat is always the start.
+    pub fn axis_period(&self, axis: usize) -> u64 {
+        let start: Vec<(i64, i64)> = self
+            .moons
+            .iter()
+            .map(|m| (m.pos[axis], m.vel[axis]))
+            .collect();
+        let mut state = start.clone();
+        let mut steps = 0;
+        loop {
+            for i in 0..state.len() {
+                for j in (i + 1)..state.len() {
+                    let pull = (state[j].0 - state[i].0).signum();
+                    state[i].1 += pull;
+                    state[j].1 -= pull;
+                }
+            }
+            for moon in state.iter_mut() {
+                moon.0 += moon.1;
+            }
+            steps += 1;
+            if state == start {
+                return steps;
+            }
+        }
+    }
+
+    /// The axes don't affect each other, so the whole system repeats once
+    /// all of them line up.
+    pub fn period(&self) -> u64 {
+        (0..3).map(|axis| self.axis_period(axis)).fold(1, lcm)
+    }
+}
+
+pub fn do_12a(input: &str, steps: usize) -> i64 {
+    let mut system = System::from_str(input).unwrap();
+    system.run(steps);
+    system.energy()
+}
+
+pub fn do_12b(input: &str) -> u64 {
+    System::from_str(input).unwrap().period()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_1: &str = "\
+<x=-1, y=0, z=2>
+<x=2, y=-10, z=-7>
+<x=4, y=-8, z=8>
+<x=3, y=5, z=-1>";
+
+    const SAMPLE_2: &str = "\
+<x=-8, y=-10, z=0>
+<x=5, y=5, z=10>
+<x=2, y=-7, z=3>
+<x=9, y=-8, z=-3>";
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Moon::from_str("<x=-1, y=0, z=2>"),
+            Ok(Moon {
+                pos: Vec3(-1, 0, 2),
+                vel: Vec3::ZERO
+            })
+        );
+        assert_eq!(Moon::from_str("<x=-1, y=0>"), Err(()));
+        assert_eq!(Moon::from_str("x=-1, y=0, z=2"), Err(()));
+    }
+
+    #[test]
+    fn part1_steps() {
+        let mut system = System::from_str(SAMPLE_1).unwrap();
+        system.step();
+        assert_eq!(system.moons[0].pos, Vec3(2, -1, 1));
+        assert_eq!(system.moons[0].vel, Vec3(3, -1, -1));
+        system.run(9);
+        assert_eq!(system.moons[3].pos, Vec3(2, 0, 4));
+        assert_eq!(system.moons[3].vel, Vec3(1, -1, -1));
+        assert_eq!(system.energy(), 179);
+    }
+
+    #[test]
+    fn part1_eg() {
+        assert_eq!(do_12a(SAMPLE_1, 10), 179);
+        assert_eq!(do_12a(SAMPLE_2, 100), 1940);
+    }
+
+    #[test]
+    fn part2_eg() {
+        let system = System::from_str(SAMPLE_1).unwrap();
+        let mut brute = system.clone();
+        brute.run(2772);
+        assert_eq!(brute, system);
+        assert_eq!(do_12b(SAMPLE_1), 2772);
+        assert_eq!(do_12b(SAMPLE_2), 4686774924);
+    }
+}
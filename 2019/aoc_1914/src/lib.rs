@@ -0,0 +1,365 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::str::FromStr;
+
+pub const ORE: &str = "ORE";
+pub const FUEL: &str = "FUEL";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    pub amount: u64,
+    pub name: String,
+}
+
+impl FromStr for Term {
+    type Err = ();
+    /// Parses `7 A`. Amounts have to be at least one; making zero of
+    /// something can't be run, and using zero of it is no input at all.
+    fn from_str(s: &str) -> Result<Term, ()> {
+        let mut parts = s.split_whitespace();
+        let amount = parts.next().ok_or(())?.parse().map_err(|_| ())?;
+        if amount == 0 {
+            return Err(());
+        }
+        let name = parts.next().ok_or(())?.to_string();
+        if parts.next().is_some() {
+            return Err(());
+        }
+        Ok(Term { amount, name })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reaction {
+    pub inputs: Vec<Term>,
+    pub output: Term,
+}
+
+impl FromStr for Reaction {
+    type Err = ();
+    /// Parses `7 A, 1 B => 1 C`
+    fn from_str(s: &str) -> Result<Reaction, ()> {
+        let mut sides = s.split("=>");
+        let inputs: Result<Vec<Term>, ()> = sides
+            .next()
+            .ok_or(())?
+            .split(',')
+            .map(Term::from_str)
+            .collect();
+        let output = Term::from_str(sides.next().ok_or(())?)?;
+        if sides.next().is_some() {
+            return Err(());
+        }
+        Ok(Reaction {
+            inputs: inputs?,
+            output,
+        })
+    }
+}
+
+/// Every reaction, keyed by what it makes, and ordered so that everything
+/// that consumes a chemical comes before the reaction that produces it.
+#[derive(Debug, Clone)]
+pub struct Reactions {
+    reactions: HashMap<String, Reaction>,
+    order: Vec<String>,
+}
+
+impl FromStr for Reactions {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Reactions, ()> {
+        let mut reactions = HashMap::new();
+        for line in s.lines().filter(|l| !l.trim().is_empty()) {
+            let reaction = Reaction::from_str(line)?;
+            if reactions
+                .insert(reaction.output.name.clone(), reaction)
+                .is_some()
+            {
+                return Err(()); // Two ways to make something makes this a much harder problem
+            }
+        }
+
+        let mut graph = Reactions {
+            reactions,
+            order: Vec::new(),
+        };
+        let mut done = HashSet::new();
+        let mut in_progress = HashSet::new();
+        graph.visit(FUEL, &mut done, &mut in_progress)?;
+        graph.order.reverse();
+        Ok(graph)
+    }
+}
+
+impl Reactions {
+    /// Depth-first postorder, so reversing it gives a topological order
+    fn visit(
+        &mut self,
+        name: &str,
+        done: &mut HashSet<String>,
+        in_progress: &mut HashSet<String>,
+    ) -> Result<(), ()> {
+        if name == ORE || done.contains(name) {
+            return Ok(());
+        }
+        if !in_progress.insert(name.to_string()) {
+            return Err(()); // Cycle
+        }
+        let inputs: Vec<String> = self
+            .reactions
+            .get(name)
+            .ok_or(())?
+            .inputs
+            .iter()
+            .map(|t| t.name.clone())
+            .collect();
+        for input in inputs {
+            self.visit(&input, done, in_progress)?;
+        }
+        in_progress.remove(name);
+        done.insert(name.to_string());
+        self.order.push(name.to_string());
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Reaction> {
+        self.reactions.get(name)
+    }
+
+    /// Chemicals in the order they get broken down, starting with FUEL
+    pub fn order(&self) -> &[String] {
+        &self.order
+    }
+
+    /// Works out how much ORE the given FUEL needs, and what's left over.
+    /// Going in topological order means every chemical's total demand is
+    /// known before we decide how many times to run its reaction.
+    pub fn requirements(&self, fuel: u64) -> (u64, HashMap<String, u64>) {
+        let mut needed: HashMap<&str, u64> = HashMap::new();
+        needed.insert(FUEL, fuel);
+        let mut leftover = HashMap::new();
+
+        for name in self.order.iter() {
+            let want = needed.get(name.as_str()).copied().unwrap_or(0);
+            let reaction = &self.reactions[name];
+            let batches = want.div_ceil(reaction.output.amount);
+            let extra = batches * reaction.output.amount - want;
+            if extra > 0 {
+                leftover.insert(name.clone(), extra);
+            }
+            for input in reaction.inputs.iter() {
+                *needed.entry(&input.name).or_insert(0) += batches * input.amount;
+            }
+        }
+        (needed.get(ORE).copied().unwrap_or(0), leftover)
+    }
+
+    pub fn ore_for_fuel(&self, fuel: u64) -> u64 {
+        self.requirements(fuel).0
+    }
+
+    /// Most FUEL you can make from a pile of ORE. ORE needed only ever goes
+    /// up with more FUEL, so we can binary search it.
+    pub fn max_fuel(&self, ore: u64) -> u64 {
+        // Every chain of reactions ends in ORE, and no amount is zero, so
+        // the cost of FUEL is positive and never goes down. Each batch only
+        // makes so much, so it also grows without limit, and the doubling
+        // has to get past `ore` eventually.
+        let mut lo = 0;
+        let mut hi = 1;
+        while self.ore_for_fuel(hi) <= ore {
+            lo = hi;
+            hi *= 2;
+        }
+        // ore_for_fuel(lo) fits, ore_for_fuel(hi) doesn't
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if self.ore_for_fuel(mid) <= ore {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    }
+
+    /// Graphviz output with an edge from each input to what it makes
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph reactions {\n");
+        writeln!(out, "    \"{}\" [shape=box];", ORE).unwrap();
+        for name in self.order.iter() {
+            let reaction = &self.reactions[name];
+            writeln!(
+                out,
+                "    \"{}\" [label=\"{} {}\"];",
+                name, reaction.output.amount, name
+            )
+            .unwrap();
+            for input in reaction.inputs.iter() {
+                writeln!(
+                    out,
+                    "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                    input.name, name, input.amount
+                )
+                .unwrap();
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+pub fn do_14a(input: &str) -> u64 {
+    Reactions::from_str(input).unwrap().ore_for_fuel(1)
+}
+
+pub fn do_14b(input: &str) -> u64 {
+    Reactions::from_str(input)
+        .unwrap()
+        .max_fuel(1_000_000_000_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_1: &str = "\
+10 ORE => 10 A
+1 ORE => 1 B
+7 A, 1 B => 1 C
+7 A, 1 C => 1 D
+7 A, 1 D => 1 E
+7 A, 1 E => 1 FUEL";
+
+    const SAMPLE_2: &str = "\
+9 ORE => 2 A
+8 ORE => 3 B
+7 ORE => 5 C
+3 A, 4 B => 1 AB
+5 B, 7 C => 1 BC
+4 C, 1 A => 1 CA
+2 AB, 3 BC, 4 CA => 1 FUEL";
+
+    const SAMPLE_3: &str = "\
+157 ORE => 5 NZVS
+165 ORE => 6 DCFZ
+44 XJWVT, 5 KHKGT, 1 QDVJ, 29 NZVS, 9 GPVTF, 48 HKGWZ => 1 FUEL
+12 HKGWZ, 1 GPVTF, 8 PSHF => 9 QDVJ
+179 ORE => 7 PSHF
+177 ORE => 5 HKGWZ
+7 DCFZ, 7 PSHF => 2 XJWVT
+165 ORE => 2 GPVTF
+3 DCFZ, 7 NZVS, 5 HKGWZ, 10 PSHF => 8 KHKGT";
+
+    const SAMPLE_4: &str = "\
+2 VPVL, 7 FWMGM, 2 CXFTF, 11 MNCFX => 1 STKFG
+17 NVRVD, 3 JNWZP => 8 VPVL
+53 STKFG, 6 MNCFX, 46 VJHF, 81 HVMC, 68 CXFTF, 25 GNMV => 1 FUEL
+22 VJHF, 37 MNCFX => 5 FWMGM
+139 ORE => 4 NVRVD
+144 ORE => 7 JNWZP
+5 MNCFX, 7 RFSQX, 2 FWMGM, 2 VPVL, 19 CXFTF => 3 HVMC
+5 VJHF, 7 MNCFX, 9 VPVL, 37 CXFTF => 6 GNMV
+145 ORE => 6 MNCFX
+1 NVRVD => 8 CXFTF
+1 VJHF, 6 MNCFX => 4 RFSQX
+176 ORE => 6 VJHF";
+
+    const SAMPLE_5: &str = "\
+171 ORE => 8 CNZTR
+7 ZLQW, 3 BMBT, 9 XCVML, 26 XMNCP, 1 WPTQ, 2 MZWV, 1 RJRHP => 4 PLWSL
+114 ORE => 4 BHXH
+14 VRPVC => 6 BMBT
+6 BHXH, 18 KTJDG, 12 WPTQ, 7 PLWSL, 31 FHTLT, 37 ZDVW => 1 FUEL
+6 WPTQ, 2 BMBT, 8 ZLQW, 18 KTJDG, 1 XMNCP, 6 MZWV, 1 RJRHP => 6 FHTLT
+15 XDBXC, 2 LTCX, 1 VRPVC => 6 ZLQW
+13 WPTQ, 10 LTCX, 3 RJRHP, 14 XMNCP, 2 MZWV, 1 ZLQW => 1 ZDVW
+5 BMBT => 4 WPTQ
+189 ORE => 9 KTJDG
+1 MZWV, 17 XDBXC, 3 XCVML => 2 XMNCP
+12 VRPVC, 27 CNZTR => 2 XDBXC
+15 KTJDG, 12 BHXH => 5 XCVML
+3 BHXH, 2 VRPVC => 7 MZWV
+121 ORE => 7 VRPVC
+7 XCVML => 6 RJRHP
+5 BHXH, 4 VRPVC => 5 LTCX";
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Reaction::from_str("7 A, 1 B => 1 C"),
+            Ok(Reaction {
+                inputs: vec!(
+                    Term {
+                        amount: 7,
+                        name: "A".to_string()
+                    },
+                    Term {
+                        amount: 1,
+                        name: "B".to_string()
+                    }
+                ),
+                output: Term {
+                    amount: 1,
+                    name: "C".to_string()
+                },
+            })
+        );
+        assert_eq!(Reaction::from_str("7 A, 1 B"), Err(()));
+        assert!(Reactions::from_str("1 ORE => 1 A").is_err()); // No FUEL
+        assert!(Reactions::from_str("1 B => 1 A\n1 A => 1 B\n1 A => 1 FUEL").is_err());
+        // Zero of anything would mean free FUEL, or dividing by zero
+        assert_eq!(Term::from_str("0 A"), Err(()));
+        assert!(Reactions::from_str("0 ORE => 2 A\n3 A => 1 FUEL").is_err());
+        assert!(Reactions::from_str("1 ORE => 0 FUEL").is_err());
+    }
+
+    #[test]
+    fn topo_order() {
+        let graph = Reactions::from_str(SAMPLE_1).unwrap();
+        assert_eq!(graph.order(), ["FUEL", "E", "D", "C", "B", "A"]);
+    }
+
+    #[test]
+    fn part1_eg() {
+        assert_eq!(do_14a(SAMPLE_1), 31);
+        assert_eq!(do_14a(SAMPLE_2), 165);
+        assert_eq!(do_14a(SAMPLE_3), 13312);
+        assert_eq!(do_14a(SAMPLE_4), 180697);
+        assert_eq!(do_14a(SAMPLE_5), 2210736);
+    }
+
+    #[test]
+    fn leftovers() {
+        let graph = Reactions::from_str(SAMPLE_1).unwrap();
+        let (ore, leftover) = graph.requirements(1);
+        assert_eq!(ore, 31);
+        assert_eq!(leftover.get("A"), Some(&2));
+        assert_eq!(leftover.get("B"), None);
+    }
+
+    #[test]
+    fn part2_eg() {
+        assert_eq!(do_14b(SAMPLE_3), 82892753);
+        assert_eq!(do_14b(SAMPLE_4), 5586022);
+        assert_eq!(do_14b(SAMPLE_5), 460664);
+    }
+
+    #[test]
+    fn cheap_fuel() {
+        // Lots of FUEL per ORE still runs out
+        let graph = Reactions::from_str("1 ORE => 1000 FUEL").unwrap();
+        assert_eq!(graph.max_fuel(0), 0);
+        assert_eq!(graph.max_fuel(3), 3000);
+    }
+
+    #[test]
+    fn dot() {
+        let dot = Reactions::from_str(SAMPLE_1).unwrap().to_dot();
+        assert!(dot.starts_with("digraph reactions {\n"));
+        assert!(dot.contains("    \"FUEL\" [label=\"1 FUEL\"];\n"));
+        assert!(dot.contains("    \"E\" -> \"FUEL\" [label=\"1\"];\n"));
+        assert!(dot.contains("    \"ORE\" -> \"A\" [label=\"10\"];\n"));
+        assert!(dot.ends_with("}\n"));
+    }
+}
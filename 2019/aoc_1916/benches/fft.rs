@@ -0,0 +1,34 @@
+use aoc_1916::{fft, parse_signal, real_message};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// Something about the size of a real puzzle input, without needing one
+fn long_signal() -> Vec<i32> {
+    let mut state: u32 = 0x1916;
+    (0..650)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            ((state >> 16) % 10) as i32
+        })
+        .collect()
+}
+
+fn bench_direct(c: &mut Criterion) {
+    let signal = long_signal();
+    c.bench_function("direct 650 digits x1 phase", |b| {
+        b.iter(|| fft(black_box(&signal), 1))
+    });
+}
+
+fn bench_suffix(c: &mut Criterion) {
+    let mut signal = long_signal();
+    // Point the offset about 90% of the way into the repeated signal
+    for (i, d) in parse_signal("5900000").into_iter().enumerate() {
+        signal[i] = d;
+    }
+    c.bench_function("suffix 6.5M digits x100 phases", |b| {
+        b.iter(|| real_message(black_box(&signal), 10000, 100).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_direct, bench_suffix);
+criterion_main!(benches);
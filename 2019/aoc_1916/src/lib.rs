@@ -0,0 +1,208 @@
+use std::fmt;
+
+const BASE_PATTERN: [i32; 4] = [0, 1, 0, -1];
+
+pub fn parse_signal(input: &str) -> Vec<i32> {
+    input
+        .trim()
+        .chars()
+        .map(|c| c.to_digit(10).unwrap() as i32)
+        .collect()
+}
+
+pub fn signal_to_str(signal: &[i32]) -> String {
+    signal
+        .iter()
+        .map(|d| std::char::from_digit(*d as u32, 10).unwrap())
+        .collect()
+}
+
+/// Multiplier applied to input digit `j` when working out output digit `i`.
+/// Each entry of the base pattern is repeated `i + 1` times, and the very
+/// first value is skipped.
+/// ```
+/// use aoc_1916::pattern;
+/// let row: Vec<i32> = (0..8).map(|j| pattern(1, j)).collect();
+/// assert_eq!(row, vec!(0, 1, 1, 0, 0, -1, -1, 0));
+/// ```
+pub fn pattern(i: usize, j: usize) -> i32 {
+    BASE_PATTERN[((j + 1) / (i + 1)) % BASE_PATTERN.len()]
+}
+
+/// One full phase, straight from the definition. O(n^2).
+pub fn phase(signal: &[i32]) -> Vec<i32> {
+    (0..signal.len())
+        .map(|i| {
+            let total: i32 = signal
+                .iter()
+                .enumerate()
+                .skip(i) // Everything before the diagonal is a zero
+                .map(|(j, d)| d * pattern(i, j))
+                .sum();
+            total.abs() % 10
+        })
+        .collect()
+}
+
+pub fn fft(signal: &[i32], phases: usize) -> Vec<i32> {
+    let mut signal = signal.to_vec();
+    for _ in 0..phases {
+        signal = phase(&signal);
+    }
+    signal
+}
+
+/// In the back half of the signal, the pattern for digit `i` is zeros up to
+/// `i` and ones after it, so each output digit is just the sum of the input
+/// from there to the end. That only holds from the midpoint on.
+pub fn suffix_phase(tail: &mut [i32]) {
+    let mut total = 0;
+    for d in tail.iter_mut().rev() {
+        total = (total + *d) % 10;
+        *d = total;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FftError {
+    /// The shortcut only works for offsets in the back half of the signal
+    OffsetInFirstHalf {
+        offset: usize,
+        len: usize,
+    },
+    /// Not enough signal after the offset for a full message
+    OffsetPastEnd {
+        offset: usize,
+        len: usize,
+    },
+    ShortSignal,
+}
+
+impl fmt::Display for FftError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FftError::OffsetInFirstHalf { offset, len } => write!(
+                f,
+                "message offset {} is in the first half of the {}-digit signal, where the suffix-sum shortcut doesn't apply",
+                offset, len
+            ),
+            FftError::OffsetPastEnd { offset, len } => write!(
+                f,
+                "message offset {} leaves no room for a message in a {}-digit signal",
+                offset, len
+            ),
+            FftError::ShortSignal => write!(f, "signal is too short to hold a message offset"),
+        }
+    }
+}
+
+impl std::error::Error for FftError {}
+
+pub const OFFSET_DIGITS: usize = 7;
+pub const MESSAGE_LEN: usize = 8;
+
+/// Repeats the signal, runs it through FFT with the suffix-sum shortcut, and
+/// reads the message at the offset given by the first seven digits.
+pub fn real_message(signal: &[i32], repeat: usize, phases: usize) -> Result<String, FftError> {
+    if signal.len() < OFFSET_DIGITS {
+        return Err(FftError::ShortSignal);
+    }
+    let offset = signal[..OFFSET_DIGITS]
+        .iter()
+        .fold(0, |acc, d| acc * 10 + *d as usize);
+    let len = signal.len() * repeat;
+    if offset < len / 2 {
+        return Err(FftError::OffsetInFirstHalf { offset, len });
+    }
+    if offset + MESSAGE_LEN > len {
+        return Err(FftError::OffsetPastEnd { offset, len });
+    }
+
+    // Only the digits from the offset on can affect the message
+    let mut tail: Vec<i32> = (offset..len).map(|i| signal[i % signal.len()]).collect();
+    for _ in 0..phases {
+        suffix_phase(&mut tail);
+    }
+    Ok(signal_to_str(&tail[..MESSAGE_LEN]))
+}
+
+pub fn do_16a(input: &str) -> String {
+    let out = fft(&parse_signal(input), 100);
+    signal_to_str(&out[..MESSAGE_LEN])
+}
+
+pub fn do_16b(input: &str) -> Result<String, FftError> {
+    real_message(&parse_signal(input), 10000, 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_phases() {
+        let mut signal = parse_signal("12345678");
+        for expected in ["48226158", "34040438", "03415518", "01029498"].iter() {
+            signal = phase(&signal);
+            assert_eq!(signal_to_str(&signal), *expected);
+        }
+    }
+
+    #[test]
+    fn part1_eg() {
+        assert_eq!(do_16a("80871224585914546619083218645595"), "24176176");
+        assert_eq!(do_16a("19617804207202209144916044189917"), "73745418");
+        assert_eq!(do_16a("69317163492948606335995924319873"), "52432133");
+    }
+
+    #[test]
+    fn shortcut_matches_direct() {
+        let signal = parse_signal("69317163492948606335995924319873");
+        let half = signal.len() / 2;
+        let mut tail = signal[half..].to_vec();
+        let direct = fft(&signal, 4);
+        for _ in 0..4 {
+            suffix_phase(&mut tail);
+        }
+        assert_eq!(tail, &direct[half..]);
+    }
+
+    #[test]
+    fn part2_eg() {
+        assert_eq!(
+            do_16b("03036732577212944063491565474664"),
+            Ok("84462026".to_string())
+        );
+        assert_eq!(
+            do_16b("02935109699940807407585447034323"),
+            Ok("78725270".to_string())
+        );
+        assert_eq!(
+            do_16b("03081770884921959731165446850517"),
+            Ok("53553731".to_string())
+        );
+    }
+
+    #[test]
+    fn part2_bad_offset() {
+        assert_eq!(
+            do_16b("00000012345678901234567890123456"),
+            Err(FftError::OffsetInFirstHalf {
+                offset: 1,
+                len: 320000
+            })
+        );
+        assert_eq!(
+            real_message(&parse_signal("00000090123456"), 1, 1),
+            Err(FftError::OffsetPastEnd { offset: 9, len: 14 })
+        );
+        assert_eq!(
+            real_message(&parse_signal("0123"), 10, 1),
+            Err(FftError::ShortSignal)
+        );
+        let msg = do_16b("00000012345678901234567890123456")
+            .unwrap_err()
+            .to_string();
+        assert!(msg.contains("first half"));
+    }
+}
@@ -0,0 +1,370 @@
+use arbgrid::{shortest_path, ArbGrid, Coord2D, Edges, Grid as _, Neighborhood};
+use std::collections::{HashSet, VecDeque};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tile {
+    #[default]
+    Wall,
+    Open,
+    Entrance,
+    Key(u8),
+    Door(u8),
+}
+
+impl Tile {
+    pub fn from_char(c: char) -> Option<Tile> {
+        match c {
+            '#' => Some(Tile::Wall),
+            '.' => Some(Tile::Open),
+            '@' => Some(Tile::Entrance),
+            'a'..='z' => Some(Tile::Key(c as u8 - b'a')),
+            'A'..='Z' => Some(Tile::Door(c as u8 - b'A')),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Tile::Wall => '#',
+            Tile::Open => '.',
+            Tile::Entrance => '@',
+            Tile::Key(k) => (b'a' + k) as char,
+            Tile::Door(d) => (b'A' + d) as char,
+        }
+    }
+}
+
+pub type Vault = ArbGrid<Tile>;
+
+/// `None` if anything in it isn't a tile
+pub fn parse_vault(input: &str) -> Option<Vault> {
+    if !input
+        .lines()
+        .flat_map(str::chars)
+        .all(|c| Tile::from_char(c).is_some())
+    {
+        return None;
+    }
+    Some(Vault::from_str_ogl(Coord2D(0, 0), input, &|c, _xy| {
+        Tile::from_char(c)
+    }))
+}
+
+/// Swaps a lone entrance for four, walled off from each other:
+/// ```text
+/// ...      @#@
+/// .@.  ->  ###
+/// ...      @#@
+/// ```
+/// Returns false if the vault doesn't have exactly one entrance.
+pub fn split_entrance(vault: &mut Vault) -> bool {
+    let entrances: Vec<Coord2D> = vault
+        .iter()
+        .filter(|(_, t)| **t == Tile::Entrance)
+        .map(|(xy, _)| *xy)
+        .collect();
+    if entrances.len() != 1 {
+        return false;
+    }
+    let Coord2D(x, y) = entrances[0];
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            let tile = if dx != 0 && dy != 0 {
+                Tile::Entrance
+            } else {
+                Tile::Wall
+            };
+            vault.insert(Coord2D(x + dx, y + dy), tile);
+        }
+    }
+    true
+}
+
+/// A set of keys (or the doors they open), one bit per letter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct KeySet(pub u32);
+
+impl KeySet {
+    pub fn with(self, key: u8) -> KeySet {
+        KeySet(self.0 | (1 << key))
+    }
+    pub fn contains(self, key: u8) -> bool {
+        self.0 & (1 << key) != 0
+    }
+    pub fn is_subset(self, other: KeySet) -> bool {
+        self.0 & !other.0 == 0
+    }
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// The shortest walk from one point of interest to a key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    pub key: u8,
+    pub dist: usize,
+    /// Doors in the way
+    pub doors: KeySet,
+    /// Other keys we'd walk over on the way
+    pub keys: KeySet,
+}
+
+/// Distances between everything that matters in the vault. Nodes 0-25 are
+/// the keys, and the entrances come after.
+#[derive(Debug, Clone)]
+pub struct KeyGraph {
+    routes: Vec<Vec<Route>>,
+    entrances: usize,
+    all_keys: KeySet,
+}
+
+pub const ENTRANCE_BASE: usize = 26;
+
+impl KeyGraph {
+    pub fn new(vault: &Vault) -> KeyGraph {
+        let mut routes = vec![Vec::new(); ENTRANCE_BASE];
+        let mut all_keys = KeySet::default();
+        let mut entrances = 0;
+        for (xy, tile) in vault.iter() {
+            match tile {
+                Tile::Key(k) => {
+                    all_keys = all_keys.with(*k);
+                    routes[*k as usize] = Self::routes_from(vault, *xy);
+                }
+                Tile::Entrance => {
+                    routes.push(Self::routes_from(vault, *xy));
+                    entrances += 1;
+                }
+                _ => {}
+            }
+        }
+        KeyGraph {
+            routes,
+            entrances,
+            all_keys,
+        }
+    }
+
+    /// Breadth-first flood from one spot, noting every key it reaches
+    fn routes_from(vault: &Vault, start: Coord2D) -> Vec<Route> {
+        let mut out = Vec::new();
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::new();
+        seen.insert(start);
+        queue.push_back((start, 0, KeySet::default(), KeySet::default()));
+
+        while let Some((xy, dist, doors, keys)) = queue.pop_front() {
+            let mut doors = doors;
+            let mut keys = keys;
+            match *vault.get(xy) {
+                Tile::Key(k) if xy != start => {
+                    out.push(Route {
+                        key: k,
+                        dist,
+                        doors,
+                        keys,
+                    });
+                    keys = keys.with(k);
+                }
+                Tile::Door(d) => doors = doors.with(d),
+                _ => {}
+            }
+            for (next, tile) in vault.neighbors(xy, Neighborhood::VonNeumann, Edges::Unbounded) {
+                if *tile != Tile::Wall && seen.insert(next) {
+                    queue.push_back((next, dist + 1, doors, keys));
+                }
+            }
+        }
+        out
+    }
+
+    pub fn routes(&self, node: usize) -> &[Route] {
+        &self.routes[node]
+    }
+
+    pub fn num_entrances(&self) -> usize {
+        self.entrances
+    }
+
+    pub fn all_keys(&self) -> KeySet {
+        self.all_keys
+    }
+
+    /// Fewest steps for the robots (one per entrance) to pick up every key
+    pub fn shortest_collection(&self) -> Option<usize> {
+        let start: Vec<u8> = (0..self.entrances)
+            .map(|i| (ENTRANCE_BASE + i) as u8)
+            .collect();
+        let (dist, _) = shortest_path(
+            (start, KeySet::default()),
+            |(robots, held)| {
+                let mut next = Vec::new();
+                for (i, node) in robots.iter().enumerate() {
+                    for route in self.routes[*node as usize].iter() {
+                        // Walking over a key we don't have yet is never
+                        // better than stopping to get it first
+                        if held.contains(route.key)
+                            || !route.doors.is_subset(*held)
+                            || !route.keys.is_subset(*held)
+                        {
+                            continue;
+                        }
+                        let mut moved = robots.clone();
+                        moved[i] = route.key;
+                        next.push(((moved, held.with(route.key)), route.dist));
+                    }
+                }
+                next
+            },
+            |(_, held)| *held == self.all_keys,
+        )?;
+        Some(dist)
+    }
+}
+
+pub fn do_18a(input: &str) -> Option<usize> {
+    KeyGraph::new(&parse_vault(input)?).shortest_collection()
+}
+
+/// Same thing, but with the entrance split four ways if it isn't already
+pub fn do_18b(input: &str) -> Option<usize> {
+    let mut vault = parse_vault(input)?;
+    split_entrance(&mut vault);
+    KeyGraph::new(&vault).shortest_collection()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_1: &str = "\
+#########
+#b.A.@.a#
+#########";
+
+    const SAMPLE_2: &str = "\
+########################
+#f.D.E.e.C.b.A.@.a.B.c.#
+######################.#
+#d.....................#
+########################";
+
+    const SAMPLE_3: &str = "\
+########################
+#...............b.C.D.f#
+#.######################
+#.....@.a.B.c.d.A.e.F.g#
+########################";
+
+    const SAMPLE_4: &str = "\
+#################
+#i.G..c...e..H.p#
+########.########
+#j.A..b...f..D.o#
+########@########
+#k.E..a...g..B.n#
+########.########
+#l.F..d...h..C.m#
+#################";
+
+    const SAMPLE_5: &str = "\
+########################
+#@..............ac.GI.b#
+###d#e#f################
+###A#B#C################
+###g#h#i################
+########################";
+
+    #[test]
+    fn routes() {
+        let graph = KeyGraph::new(&parse_vault(SAMPLE_1).unwrap());
+        assert_eq!(graph.num_entrances(), 1);
+        assert_eq!(graph.all_keys(), KeySet(0b11));
+        let mut from_start = graph.routes(ENTRANCE_BASE).to_vec();
+        from_start.sort_by_key(|r| r.key);
+        assert_eq!(
+            from_start,
+            vec!(
+                Route {
+                    key: 0,
+                    dist: 2,
+                    doors: KeySet(0),
+                    keys: KeySet(0)
+                },
+                Route {
+                    key: 1,
+                    dist: 4,
+                    doors: KeySet(0b1),
+                    keys: KeySet(0)
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn part1_eg() {
+        assert_eq!(do_18a(SAMPLE_1), Some(8));
+        assert_eq!(do_18a(SAMPLE_2), Some(86));
+        assert_eq!(do_18a(SAMPLE_3), Some(132));
+        assert_eq!(do_18a(SAMPLE_4), Some(136));
+        assert_eq!(do_18a(SAMPLE_5), Some(81));
+        assert_eq!(do_18a("#####\n#@?a#\n#####"), None);
+    }
+
+    #[test]
+    fn split() {
+        let mut vault = parse_vault("#####\n#...#\n#.@.#\n#...#\n#####").unwrap();
+        assert!(split_entrance(&mut vault));
+        assert_eq!(
+            vault.to_string_ogl(&|t, _xy| t.to_char()),
+            "#####\n#@#@#\n#####\n#@#@#\n#####\n"
+        );
+        assert!(!split_entrance(&mut vault));
+    }
+
+    #[test]
+    fn part2_eg() {
+        let sample_1 = "\
+#######
+#a.#Cd#
+##...##
+##.@.##
+##...##
+#cB#Ab#
+#######";
+        let sample_2 = "\
+###############
+#d.ABC.#.....a#
+######@#@######
+###############
+######@#@######
+#b.....#.....c#
+###############";
+        let sample_3 = "\
+#############
+#DcBa.#.GhKl#
+#.###@#@#I###
+#e#d#####j#k#
+###C#@#@###J#
+#fEbA.#.FgHi#
+#############";
+        let sample_4 = "\
+#############
+#g#f.D#..h#l#
+#F###e#E###.#
+#dCba@#@BcIJ#
+#############
+#nK.L@#@G...#
+#M###N#H###.#
+#o#m..#i#jk.#
+#############";
+        assert_eq!(do_18b(sample_1), Some(8));
+        assert_eq!(do_18b(sample_2), Some(24));
+        assert_eq!(do_18b(sample_3), Some(32));
+        assert_eq!(do_18b(sample_4), Some(72));
+    }
+}
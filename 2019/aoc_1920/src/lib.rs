@@ -0,0 +1,347 @@
+use arbgrid::{ArbGrid, Coord2D, Edges, Grid as _, Neighborhood};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tile {
+    #[default]
+    Empty,
+    Wall,
+    Open,
+    Letter(char),
+}
+
+impl Tile {
+    /// `None` for anything that can't be in a maze
+    pub fn from_char(c: char) -> Option<Tile> {
+        match c {
+            ' ' => Some(Tile::Empty),
+            '#' => Some(Tile::Wall),
+            '.' => Some(Tile::Open),
+            'A'..='Z' => Some(Tile::Letter(c)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// On the outside edge of the donut; goes up a level when recursing
+    Outer,
+    /// On the edge of the hole; goes down a level
+    Inner,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Portal {
+    pub label: String,
+    /// The open tile next to the label
+    pub pos: Coord2D,
+    pub side: Side,
+}
+
+pub const START: &str = "AA";
+pub const END: &str = "ZZ";
+
+pub struct Maze {
+    grid: ArbGrid<Tile>,
+    portals: Vec<Portal>,
+    /// Where each portal tile leads, and which way it changes the level
+    warps: HashMap<Coord2D, (Coord2D, Side)>,
+    start: Coord2D,
+    end: Coord2D,
+}
+
+impl Maze {
+    pub fn parse(input: &str) -> Option<Maze> {
+        if !input
+            .lines()
+            .flat_map(str::chars)
+            .all(|c| Tile::from_char(c).is_some())
+        {
+            return None;
+        }
+        let grid = ArbGrid::from_str_ogl(Coord2D(0, 0), input, &|c, _xy| {
+            Tile::from_char(c).filter(|t| *t != Tile::Empty)
+        });
+
+        // Labels sit outside the maze proper, so it's the walls and floor
+        // that tell us where the outer edge is
+        let maze_cells: Vec<Coord2D> = grid
+            .iter()
+            .filter(|(_, t)| **t == Tile::Wall || **t == Tile::Open)
+            .map(|(xy, _)| *xy)
+            .collect();
+        let min_x = maze_cells.iter().map(|xy| xy.0).min()?;
+        let max_x = maze_cells.iter().map(|xy| xy.0).max()?;
+        let min_y = maze_cells.iter().map(|xy| xy.1).min()?;
+        let max_y = maze_cells.iter().map(|xy| xy.1).max()?;
+
+        let mut portals = Vec::new();
+        for (xy, tile) in grid.iter() {
+            if *tile != Tile::Open {
+                continue;
+            }
+            for (near_xy, near) in grid.neighbors(*xy, Neighborhood::VonNeumann, Edges::Unbounded) {
+                if let Tile::Letter(near_c) = near {
+                    // The other half of the label is one further out
+                    let far_xy = Coord2D(2 * near_xy.0 - xy.0, 2 * near_xy.1 - xy.1);
+                    let far_c = match grid.get(far_xy) {
+                        Tile::Letter(c) => *c,
+                        _ => return None,
+                    };
+                    // Labels read left to right or top to bottom
+                    let label: String = if far_xy < near_xy {
+                        [far_c, *near_c].iter().collect()
+                    } else {
+                        [*near_c, far_c].iter().collect()
+                    };
+                    let side = if xy.0 == min_x || xy.0 == max_x || xy.1 == min_y || xy.1 == max_y {
+                        Side::Outer
+                    } else {
+                        Side::Inner
+                    };
+                    portals.push(Portal {
+                        label,
+                        pos: *xy,
+                        side,
+                    });
+                }
+            }
+        }
+
+        let find =
+            |label: &str| -> Vec<&Portal> { portals.iter().filter(|p| p.label == label).collect() };
+        let start = match find(START).as_slice() {
+            [p] => p.pos,
+            _ => return None,
+        };
+        let end = match find(END).as_slice() {
+            [p] => p.pos,
+            _ => return None,
+        };
+
+        let mut warps = HashMap::new();
+        for portal in portals.iter() {
+            if portal.label == START || portal.label == END {
+                continue;
+            }
+            let pair = find(&portal.label);
+            if pair.len() != 2 {
+                return None;
+            }
+            let other = if pair[0].pos == portal.pos {
+                pair[1]
+            } else {
+                pair[0]
+            };
+            warps.insert(portal.pos, (other.pos, portal.side));
+        }
+
+        Some(Maze {
+            grid,
+            portals,
+            warps,
+            start,
+            end,
+        })
+    }
+
+    pub fn portals(&self) -> &[Portal] {
+        &self.portals
+    }
+
+    /// How deep a shortest recursive route can possibly go: one level per
+    /// pair of (inner portal taken down, outer portal taken back up).
+    ///
+    /// Say a route goes deeper. For each level k, take the last trip down
+    /// into k before the deepest point and the first trip back out after it;
+    /// between them the route stays at k or below. Two levels j > k must use
+    /// the same pair of portals, and j's trip sits inside k's. Every level
+    /// but the top is laid out the same, so j's trip moved up to level k
+    /// does k's job in fewer steps, and the route wasn't the shortest.
+    pub fn max_useful_depth(&self) -> usize {
+        let count = |side| self.warps.values().filter(|(_, s)| *s == side).count();
+        count(Side::Inner) * count(Side::Outer)
+    }
+
+    /// Everywhere you can get to in one step, with the change in level
+    fn moves(&self, xy: Coord2D) -> Vec<(Coord2D, i32)> {
+        let mut out: Vec<(Coord2D, i32)> = self
+            .grid
+            .neighbors(xy, Neighborhood::VonNeumann, Edges::Unbounded)
+            .filter(|(_, t)| **t == Tile::Open)
+            .map(|(next, _)| (next, 0))
+            .collect();
+        if let Some((dest, side)) = self.warps.get(&xy) {
+            out.push((
+                *dest,
+                match side {
+                    Side::Inner => 1,
+                    Side::Outer => -1,
+                },
+            ));
+        }
+        out
+    }
+
+    /// Breadth-first from AA to ZZ, with portals as plain shortcuts
+    pub fn shortest_flat(&self) -> Option<usize> {
+        self.search(|_level, _delta| Some(0))
+    }
+
+    /// Same again, but inner portals go down a level and outer ones come
+    /// back up. Outer portals are walls at the top level, and ZZ only counts
+    /// there. Gives up below `max_depth`.
+    pub fn shortest_recursive(&self, max_depth: usize) -> Option<usize> {
+        self.search(|level, delta| {
+            let next = level as i32 + delta;
+            if next < 0 || next as usize > max_depth {
+                None
+            } else {
+                Some(next as usize)
+            }
+        })
+    }
+
+    fn search(&self, next_level: impl Fn(usize, i32) -> Option<usize>) -> Option<usize> {
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::new();
+        seen.insert((self.start, 0));
+        queue.push_back((self.start, 0, 0));
+
+        while let Some((xy, level, dist)) = queue.pop_front() {
+            if xy == self.end && level == 0 {
+                return Some(dist);
+            }
+            for (next, delta) in self.moves(xy) {
+                if let Some(next_level) = next_level(level, delta) {
+                    if seen.insert((next, next_level)) {
+                        queue.push_back((next, next_level, dist + 1));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+pub fn do_20a(input: &str) -> Option<usize> {
+    Maze::parse(input)?.shortest_flat()
+}
+
+/// Only searches as deep as `max_useful_depth`, so a maze with no way out
+/// gives `None` instead of searching forever
+pub fn do_20b(input: &str) -> Option<usize> {
+    let maze = Maze::parse(input)?;
+    maze.shortest_recursive(maze.max_useful_depth())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_1: &str = "         A           
+         A           
+  #######.#########  
+  #######.........#  
+  #######.#######.#  
+  #######.#######.#  
+  #######.#######.#  
+  #####  B    ###.#  
+BC...##  C    ###.#  
+  ##.##       ###.#  
+  ##...DE  F  ###.#  
+  #####    G  ###.#  
+  #########.#####.#  
+DE..#######...###.#  
+  #.#########.###.#  
+FG..#########.....#  
+  ###########.#####  
+             Z       
+             Z       ";
+
+    #[test]
+    fn parse_portals() {
+        let maze = Maze::parse(SAMPLE_1).unwrap();
+        let mut portals: Vec<(String, Coord2D, Side)> = maze
+            .portals()
+            .iter()
+            .map(|p| (p.label.clone(), p.pos, p.side))
+            .collect();
+        portals.sort_by(|a, b| (&a.0, a.1).cmp(&(&b.0, b.1)));
+        assert_eq!(
+            portals,
+            vec!(
+                ("AA".to_string(), Coord2D(9, 2), Side::Outer),
+                ("BC".to_string(), Coord2D(2, 8), Side::Outer),
+                ("BC".to_string(), Coord2D(9, 6), Side::Inner),
+                ("DE".to_string(), Coord2D(2, 13), Side::Outer),
+                ("DE".to_string(), Coord2D(6, 10), Side::Inner),
+                ("FG".to_string(), Coord2D(2, 15), Side::Outer),
+                ("FG".to_string(), Coord2D(11, 12), Side::Inner),
+                ("ZZ".to_string(), Coord2D(13, 16), Side::Outer),
+            )
+        );
+    }
+
+    #[test]
+    fn unpaired_portal() {
+        let broken = SAMPLE_1.replace("FG..#", "FH..#");
+        assert!(Maze::parse(&broken).is_none());
+    }
+
+    #[test]
+    fn bad_tile() {
+        let broken = SAMPLE_1.replace("BC...", "BC.?.");
+        assert!(Maze::parse(&broken).is_none());
+        assert_eq!(do_20a(&broken), None);
+    }
+
+    #[test]
+    fn part1_eg() {
+        assert_eq!(do_20a(SAMPLE_1), Some(23));
+    }
+
+    #[test]
+    fn part2_eg() {
+        assert_eq!(do_20b(SAMPLE_1), Some(26));
+    }
+
+    /// AA and the outer XY share one region, and ZZ sits with the inner XY.
+    /// Flat, XY is a shortcut. Recursive, the outer XY is a wall until we
+    /// drop a level through PQ.
+    const SAMPLE_DEPTH: &str = "    A     Z
+    A     Z
+  ##.#####.##
+  ...##....##
+  .####.#####
+  .##  X  ###
+XY.##  Y  ###
+  ...PQ   ###
+  .##     ###
+  .##     ###
+  .##########
+  ..#########
+  #.#########
+   P
+   Q";
+
+    #[test]
+    fn part2_needs_depth() {
+        assert_eq!(do_20a(SAMPLE_DEPTH), Some(12));
+        assert_eq!(do_20b(SAMPLE_DEPTH), Some(23));
+
+        // Without PQ there's no way down, so no way out
+        let no_pq = SAMPLE_DEPTH
+            .replace("...PQ", "...##")
+            .replace("   P\n   Q", "   \n   ");
+        assert_eq!(do_20a(&no_pq), Some(12));
+        assert_eq!(do_20b(&no_pq), None);
+
+        // XY and PQ each have an inner and an outer end
+        let maze = Maze::parse(SAMPLE_DEPTH).unwrap();
+        assert_eq!(maze.max_useful_depth(), 4);
+        assert_eq!(maze.shortest_recursive(0), None);
+        assert_eq!(maze.shortest_recursive(100), Some(23));
+    }
+}
@@ -0,0 +1,235 @@
+use aocmath::{modinv, mulmod};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Technique {
+    NewStack,
+    Cut(i64),
+    Increment(i64),
+}
+
+impl FromStr for Technique {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Technique, ()> {
+        let s = s.trim();
+        if s == "deal into new stack" {
+            Ok(Technique::NewStack)
+        } else if let Some(n) = s.strip_prefix("cut ") {
+            Ok(Technique::Cut(n.parse().map_err(|_| ())?))
+        } else if let Some(n) = s.strip_prefix("deal with increment ") {
+            Ok(Technique::Increment(n.parse().map_err(|_| ())?))
+        } else {
+            Err(())
+        }
+    }
+}
+
+/// `x -> a*x + b (mod n)`, for mapping where a card was to where it ends up.
+/// Every technique is one of these, and so is any sequence of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Affine {
+    pub a: i64,
+    pub b: i64,
+    pub n: i64,
+}
+
+impl Affine {
+    pub fn identity(n: i64) -> Affine {
+        Affine { a: 1, b: 0, n }
+    }
+
+    pub fn from_technique(tech: Technique, n: i64) -> Affine {
+        let (a, b) = match tech {
+            // Reverse: x -> n - 1 - x
+            Technique::NewStack => (-1, -1),
+            // Everything slides down: x -> x - c
+            Technique::Cut(c) => (1, -c),
+            // Card at x goes to x*i
+            Technique::Increment(i) => (i, 0),
+        };
+        Affine {
+            a: a.rem_euclid(n),
+            b: b.rem_euclid(n),
+            n,
+        }
+    }
+
+    /// Composes a whole shuffle, applied in order
+    pub fn from_techniques(techs: &[Technique], n: i64) -> Affine {
+        techs.iter().fold(Affine::identity(n), |acc, t| {
+            acc.then(Affine::from_technique(*t, n))
+        })
+    }
+
+    pub fn apply(self, x: i64) -> i64 {
+        (mulmod(self.a, x, self.n) + self.b).rem_euclid(self.n)
+    }
+
+    /// `self` followed by `other`
+    pub fn then(self, other: Affine) -> Affine {
+        assert_eq!(self.n, other.n);
+        Affine {
+            a: mulmod(other.a, self.a, self.n),
+            b: (mulmod(other.a, self.b, self.n) + other.b).rem_euclid(self.n),
+            n: self.n,
+        }
+    }
+
+    /// Applying this `times` times over, by repeated squaring
+    pub fn pow(self, times: u64) -> Affine {
+        let mut result = Affine::identity(self.n);
+        let mut square = self;
+        let mut times = times;
+        while times > 0 {
+            if times & 1 == 1 {
+                result = result.then(square);
+            }
+            square = square.then(square);
+            times >>= 1;
+        }
+        result
+    }
+
+    /// Where a card must have started to end up at a given spot. Only exists
+    /// if `a` is coprime with the deck size, which it always is for a prime.
+    pub fn inverse(self) -> Option<Affine> {
+        let a_inv = modinv(self.a, self.n)?;
+        Some(Affine {
+            a: a_inv,
+            b: mulmod(-self.b, a_inv, self.n),
+            n: self.n,
+        })
+    }
+}
+
+pub fn parse_techniques(input: &str) -> Vec<Technique> {
+    input
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| Technique::from_str(l).unwrap())
+        .collect()
+}
+
+/// The whole deck after a shuffle, top to bottom
+pub fn shuffle_deck(techs: &[Technique], n: i64) -> Vec<i64> {
+    let shuffle = Affine::from_techniques(techs, n);
+    let mut deck = vec![0; n as usize];
+    for card in 0..n {
+        deck[shuffle.apply(card) as usize] = card;
+    }
+    deck
+}
+
+/// Where card 2019 ends up in a deck of 10007
+pub fn do_22a(input: &str) -> i64 {
+    Affine::from_techniques(&parse_techniques(input), 10007).apply(2019)
+}
+
+pub const HUGE_DECK: i64 = 119_315_717_514_047;
+pub const HUGE_REPEATS: u64 = 101_741_582_076_661;
+
+/// Which card ends up at 2020 after shuffling the huge deck a huge number of times
+pub fn do_22b(input: &str) -> i64 {
+    Affine::from_techniques(&parse_techniques(input), HUGE_DECK)
+        .pow(HUGE_REPEATS)
+        .inverse()
+        .unwrap()
+        .apply(2020)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The slow way, for checking against
+    fn naive(techs: &[Technique], n: i64) -> Vec<i64> {
+        let mut deck: Vec<i64> = (0..n).collect();
+        for tech in techs {
+            match *tech {
+                Technique::NewStack => deck.reverse(),
+                Technique::Cut(c) => deck.rotate_left(c.rem_euclid(n) as usize),
+                Technique::Increment(i) => {
+                    let mut next = vec![0; n as usize];
+                    for (pos, card) in deck.iter().enumerate() {
+                        next[(pos as i64 * i % n) as usize] = *card;
+                    }
+                    deck = next;
+                }
+            }
+        }
+        deck
+    }
+
+    #[test]
+    fn part1_eg() {
+        let samples = [
+            (
+                "deal with increment 7\ndeal into new stack\ndeal into new stack",
+                vec![0, 3, 6, 9, 2, 5, 8, 1, 4, 7],
+            ),
+            (
+                "cut 6\ndeal with increment 7\ndeal into new stack",
+                vec![3, 0, 7, 4, 1, 8, 5, 2, 9, 6],
+            ),
+            (
+                "deal with increment 7\ndeal with increment 9\ncut -2",
+                vec![6, 3, 0, 7, 4, 1, 8, 5, 2, 9],
+            ),
+            (
+                "deal into new stack
+cut -2
+deal with increment 7
+cut 8
+cut -4
+deal with increment 7
+cut 3
+deal with increment 9
+deal with increment 3
+cut -1",
+                vec![9, 2, 5, 8, 1, 4, 7, 0, 3, 6],
+            ),
+        ];
+        for (input, expected) in samples.iter() {
+            let techs = parse_techniques(input);
+            assert_eq!(&shuffle_deck(&techs, 10), expected);
+            assert_eq!(&naive(&techs, 10), expected);
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Technique::from_str("cut -42"), Ok(Technique::Cut(-42)));
+        assert_eq!(
+            Technique::from_str("deal with increment 3"),
+            Ok(Technique::Increment(3))
+        );
+        assert_eq!(Technique::from_str("deal with fate"), Err(()));
+    }
+
+    #[test]
+    fn repeated() {
+        let techs = parse_techniques("cut 3\ndeal with increment 3\ndeal into new stack\ncut -5");
+        let n = 11;
+        let once = Affine::from_techniques(&techs, n);
+        let mut deck: Vec<i64> = (0..n).collect();
+        for times in 0..20 {
+            let shuffle = once.pow(times);
+            let unshuffle = shuffle.inverse().unwrap();
+            for card in 0..n {
+                assert_eq!(deck[shuffle.apply(card) as usize], card);
+                assert_eq!(unshuffle.apply(card), deck[card as usize]);
+            }
+            // Shuffling a shuffled deck is the same as reordering it the way
+            // one shuffle reorders a sorted one
+            deck = naive(&techs, n).iter().map(|c| deck[*c as usize]).collect();
+        }
+    }
+
+    #[test]
+    fn huge_inverse() {
+        let techs = parse_techniques("deal with increment 7\ncut 123456789\ndeal into new stack");
+        let shuffle = Affine::from_techniques(&techs, HUGE_DECK).pow(HUGE_REPEATS);
+        let card = shuffle.inverse().unwrap().apply(2020);
+        assert_eq!(shuffle.apply(card), 2020);
+    }
+}
@@ -0,0 +1,246 @@
+use arbgrid::{ArbGrid, Automaton, Coord2D, Edges, Grid as _, Neighborhood};
+use std::collections::BTreeMap;
+
+pub const SIZE: i32 = 5;
+pub const CENTER: Coord2D = Coord2D(2, 2);
+
+pub type Eris = ArbGrid<bool>;
+
+/// Only bugs get stored, so `iter()` is just the bugs. Top-left is (0, 0).
+pub fn parse(input: &str) -> Eris {
+    Eris::from_str_ogl(Coord2D(0, 0), input, &|c, _xy| match c {
+        '#' => Some(true),
+        '.' | '?' => None,
+        _ => panic!("Unexpected tile {:?}", c),
+    })
+}
+
+fn all_tiles() -> impl Iterator<Item = Coord2D> {
+    (0..SIZE).flat_map(|y| (0..SIZE).map(move |x| Coord2D(x, y)))
+}
+
+/// A bug survives with exactly one neighbor, and an empty tile gets one
+/// with one or two.
+fn lives(bug: bool, neighbors: usize) -> bool {
+    neighbors == 1 || (!bug && neighbors == 2)
+}
+
+pub fn step(grid: &Eris) -> Eris {
+    let mut next = Eris::new(false);
+    for xy in all_tiles() {
+        let neighbors = grid
+            .neighbors(xy, Neighborhood::VonNeumann, Edges::Unbounded)
+            .filter(|(_, b)| **b)
+            .count();
+        if lives(*grid.get(xy), neighbors) {
+            next.insert(xy, true);
+        }
+    }
+    next
+}
+
+/// Each tile is worth the next power of two, reading order
+pub fn biodiversity(grid: &Eris) -> u32 {
+    grid.iter()
+        .filter(|(_, bug)| **bug)
+        .map(|(xy, _)| 1 << (xy.1 * SIZE + xy.0))
+        .sum()
+}
+
+/// Biodiversity of the first layout to show up twice
+pub fn first_repeat(grid: &Eris) -> u32 {
+    let bounds = (Coord2D(0, 0), Coord2D(SIZE - 1, SIZE - 1));
+    let mut eris = Automaton::bounded(grid.clone(), bounds, Neighborhood::VonNeumann);
+    // There are only 2^25 layouts, so something has to repeat. Biodiversity
+    // is one bit per tile, so it's the layout in a single u32.
+    eris.find_cycle_by(
+        |cell| lives(*cell.value, cell.count(|b| *b)),
+        biodiversity,
+        1 << 25,
+    )
+    .expect("more layouts than fit in the grid");
+    biodiversity(eris.grid())
+}
+
+/// Infinitely nested grids. Level +1 is the grid inside the middle tile of
+/// level 0, and level -1 is the one that level 0 sits inside of.
+#[derive(Debug, Clone, Default)]
+pub struct LevelGrid {
+    levels: BTreeMap<i32, Eris>,
+}
+
+impl LevelGrid {
+    pub fn new(level_zero: Eris) -> LevelGrid {
+        let mut levels = BTreeMap::new();
+        levels.insert(0, level_zero);
+        LevelGrid { levels }
+    }
+
+    pub fn get(&self, level: i32, xy: Coord2D) -> bool {
+        self.levels.get(&level).is_some_and(|g| *g.get(xy))
+    }
+
+    pub fn level(&self, level: i32) -> Option<&Eris> {
+        self.levels.get(&level)
+    }
+
+    /// Every tile touching this one, across levels. Ranges from four (on
+    /// a corner) to eight (next to the middle).
+    pub fn neighbors(level: i32, xy: Coord2D) -> Vec<(i32, Coord2D)> {
+        let mut out = Vec::new();
+        for (dx, dy) in [(0, -1), (0, 1), (-1, 0), (1, 0)].iter() {
+            let next = Coord2D(xy.0 + dx, xy.1 + dy);
+            if next.0 < 0 || next.0 >= SIZE || next.1 < 0 || next.1 >= SIZE {
+                // Off the edge is the tile next to the middle of the outer grid
+                out.push((level - 1, Coord2D(CENTER.0 + dx, CENTER.1 + dy)));
+            } else if next == CENTER {
+                // Into the middle is a whole edge of the inner grid
+                for i in 0..SIZE {
+                    let inner = match (dx, dy) {
+                        (1, 0) => Coord2D(0, i),
+                        (-1, 0) => Coord2D(SIZE - 1, i),
+                        (0, 1) => Coord2D(i, 0),
+                        _ => Coord2D(i, SIZE - 1),
+                    };
+                    out.push((level + 1, inner));
+                }
+            } else {
+                out.push((level, next));
+            }
+        }
+        out
+    }
+
+    pub fn step(&self) -> LevelGrid {
+        let (lowest, highest) = match (self.levels.keys().next(), self.levels.keys().last()) {
+            (Some(lo), Some(hi)) => (*lo, *hi),
+            _ => return self.clone(),
+        };
+
+        let mut levels = BTreeMap::new();
+        for level in (lowest - 1)..=(highest + 1) {
+            let mut next = Eris::new(false);
+            for xy in all_tiles().filter(|xy| *xy != CENTER) {
+                let neighbors = Self::neighbors(level, xy)
+                    .into_iter()
+                    .filter(|(l, n)| self.get(*l, *n))
+                    .count();
+                if lives(self.get(level, xy), neighbors) {
+                    next.insert(xy, true);
+                }
+            }
+            // Keep the empty levels from piling up at either end
+            if next.bounds().is_some() {
+                levels.insert(level, next);
+            }
+        }
+        LevelGrid { levels }
+    }
+
+    pub fn count(&self) -> usize {
+        self.levels
+            .values()
+            .map(|g| g.iter().filter(|(_, bug)| **bug).count())
+            .sum()
+    }
+}
+
+pub fn do_24a(input: &str) -> u32 {
+    first_repeat(&parse(input))
+}
+
+pub fn do_24b(input: &str, minutes: usize) -> usize {
+    let mut grid = LevelGrid::new(parse(input));
+    for _ in 0..minutes {
+        grid = grid.step();
+    }
+    grid.count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+....#
+#..#.
+#..##
+..#..
+#....";
+
+    fn show(grid: &Eris) -> String {
+        let mut full = grid.clone();
+        // Pin the corners so the whole 5x5 gets drawn
+        full.insert(Coord2D(0, 0), *grid.get(Coord2D(0, 0)));
+        full.insert(Coord2D(4, 4), *grid.get(Coord2D(4, 4)));
+        full.to_string_ogl(&|bug, _xy| if *bug { '#' } else { '.' })
+    }
+
+    #[test]
+    fn part1_steps() {
+        let mut grid = parse(SAMPLE);
+        grid = step(&grid);
+        assert_eq!(show(&grid), "#..#.\n####.\n###.#\n##.##\n.##..\n");
+        grid = step(&grid);
+        assert_eq!(show(&grid), "#####\n....#\n....#\n...#.\n#.###\n");
+    }
+
+    #[test]
+    fn bio() {
+        let grid = parse(".....\n.....\n.....\n#....\n.#...");
+        assert_eq!(biodiversity(&grid), 2129920);
+    }
+
+    #[test]
+    fn part1_eg() {
+        assert_eq!(do_24a(SAMPLE), 2129920);
+    }
+
+    #[test]
+    fn recursive_neighbors() {
+        // Corner: two on this level, two on the outer one
+        let mut corner = LevelGrid::neighbors(0, Coord2D(0, 0));
+        corner.sort();
+        assert_eq!(
+            corner,
+            vec!(
+                (-1, Coord2D(1, 2)),
+                (-1, Coord2D(2, 1)),
+                (0, Coord2D(0, 1)),
+                (0, Coord2D(1, 0))
+            )
+        );
+
+        // Above the middle: three here, plus the whole top row inside
+        let above = LevelGrid::neighbors(3, Coord2D(2, 1));
+        assert_eq!(above.len(), 8);
+        assert_eq!(above.iter().filter(|(l, _)| *l == 4).count(), 5);
+        assert!(above.contains(&(4, Coord2D(4, 0))));
+
+        // Plain tile
+        assert_eq!(LevelGrid::neighbors(0, Coord2D(3, 3)).len(), 4);
+
+        // Neighbors are always mutual
+        for level in -1..=1 {
+            for xy in all_tiles().filter(|xy| *xy != CENTER) {
+                for (l, n) in LevelGrid::neighbors(level, xy) {
+                    assert!(LevelGrid::neighbors(l, n).contains(&(level, xy)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn part2_eg() {
+        let mut grid = LevelGrid::new(parse(SAMPLE));
+        for _ in 0..10 {
+            grid = grid.step();
+        }
+        assert_eq!(grid.count(), 99);
+        assert_eq!(
+            show(grid.level(0).unwrap()),
+            ".#...\n.#.##\n.#...\n.....\n.....\n"
+        );
+        assert_eq!(do_24b(SAMPLE, 10), 99);
+    }
+}
@@ -0,0 +1,690 @@
+use intcode::{IntMachine, RunMode};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{self, BufRead, Write};
+
+/// Enough for any sane command; the real ones take well under a tenth of this.
+pub const DEFAULT_BUDGET: usize = 1_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Door {
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Door {
+    pub fn from_word(s: &str) -> Option<Door> {
+        match s {
+            "north" => Some(Door::North),
+            "south" => Some(Door::South),
+            "east" => Some(Door::East),
+            "west" => Some(Door::West),
+            _ => None,
+        }
+    }
+
+    pub fn command(self) -> &'static str {
+        match self {
+            Door::North => "north",
+            Door::South => "south",
+            Door::East => "east",
+            Door::West => "west",
+        }
+    }
+
+    pub fn opposite(self) -> Door {
+        match self {
+            Door::North => Door::South,
+            Door::South => Door::North,
+            Door::East => Door::West,
+            Door::West => Door::East,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Room {
+    pub name: String,
+    pub doors: Vec<Door>,
+    pub items: Vec<String>,
+}
+
+impl Room {
+    /// Parses the last room description in a chunk of output. Getting thrown
+    /// out of a room prints both that room and the one you land in, and we
+    /// only care about where we ended up.
+    pub fn parse_last(text: &str) -> Option<Room> {
+        let start = text.rfind("== ")?;
+        let mut lines = text[start..].lines();
+        let name = lines
+            .next()?
+            .trim()
+            .trim_start_matches("== ")
+            .trim_end_matches(" ==")
+            .to_string();
+
+        let mut doors = Vec::new();
+        let mut items = Vec::new();
+        let mut in_doors = false;
+        for line in lines {
+            let line = line.trim();
+            if line == "Doors here lead:" {
+                in_doors = true;
+            } else if line == "Items here:" {
+                in_doors = false;
+            } else if let Some(entry) = line.strip_prefix("- ") {
+                if in_doors {
+                    doors.push(Door::from_word(entry)?);
+                } else {
+                    items.push(entry.to_string());
+                }
+            } else if line == "Command?" {
+                break;
+            }
+        }
+        Some(Room { name, doors, items })
+    }
+}
+
+/// The airlock password is the only number Santa ever tells you.
+/// ```
+/// use aoc_1925::find_password;
+/// let msg = "\"Oh, hello! You should be able to get in by typing 1234 on the keypad at the main airlock.\"";
+/// assert_eq!(find_password(msg), Some("1234".to_string()));
+/// assert_eq!(find_password("Command?"), None);
+/// ```
+pub fn find_password(text: &str) -> Option<String> {
+    let start = text.find("typing ")? + "typing ".len();
+    let digits: String = text[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    if digits.is_empty() {
+        None
+    } else {
+        Some(digits)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Reply {
+    /// Waiting for the next command
+    Prompt(String),
+    /// The program ended, either because we won or because we died
+    Halted(String),
+    /// Ran out of budget without asking for input
+    Hung(String),
+}
+
+impl Reply {
+    pub fn text(&self) -> &str {
+        match self {
+            Reply::Prompt(s) | Reply::Halted(s) | Reply::Hung(s) => s,
+        }
+    }
+}
+
+/// Anything that can be sent commands like the droid. Cloning has to take a
+/// full snapshot, since that's how we try out risky things safely.
+pub trait Droid: Clone {
+    /// Runs up to the first prompt without sending anything
+    fn wake(&mut self) -> Reply;
+    fn command(&mut self, cmd: &str) -> Reply;
+}
+
+#[derive(Clone)]
+pub struct IntDroid {
+    cpu: IntMachine,
+    pub budget: usize,
+}
+
+impl IntDroid {
+    pub fn new(cpu: IntMachine) -> IntDroid {
+        IntDroid {
+            cpu,
+            budget: DEFAULT_BUDGET,
+        }
+    }
+
+    fn run_to_prompt(&mut self) -> Reply {
+        let mode = self.cpu.run_for(self.budget);
+        let text: String = self.cpu.output.drain(..).map(|a| a as u8 as char).collect();
+        match mode {
+            RunMode::InputStalled => Reply::Prompt(text),
+            RunMode::EndPgm => Reply::Halted(text),
+            RunMode::Running => Reply::Hung(text),
+        }
+    }
+}
+
+impl Droid for IntDroid {
+    fn wake(&mut self) -> Reply {
+        self.run_to_prompt()
+    }
+
+    fn command(&mut self, cmd: &str) -> Reply {
+        for b in cmd.bytes().chain(std::iter::once(b'\n')) {
+            self.cpu.feed_one(b.into());
+        }
+        self.run_to_prompt()
+    }
+}
+
+/// Plays the game by hand, one command per input line.
+pub fn play<D: Droid, R: BufRead, W: Write>(
+    droid: &mut D,
+    input: R,
+    mut output: W,
+) -> io::Result<()> {
+    let mut reply = droid.wake();
+    let mut lines = input.lines();
+    loop {
+        write!(output, "{}", reply.text())?;
+        match reply {
+            Reply::Prompt(_) => {}
+            Reply::Halted(_) => break,
+            Reply::Hung(_) => {
+                writeln!(output, "\n(the droid stopped responding)")?;
+                break;
+            }
+        }
+        output.flush()?;
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+        reply = droid.command(line.trim());
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AdventureError {
+    /// Something we thought was safe wasn't
+    Died(String),
+    /// Expected to be in a room, but the output didn't describe one
+    NoRoom(String),
+    NoCheckpoint,
+    NoPassword,
+}
+
+pub struct Explorer<D: Droid> {
+    droid: D,
+    here: String,
+    rooms: HashMap<String, Room>,
+    links: HashMap<(String, Door), String>,
+    carrying: Vec<String>,
+    dangerous: Vec<String>,
+    /// The room before the pressure-sensitive floor, and which way it is
+    checkpoint: Option<(String, Door)>,
+    password: Option<String>,
+}
+
+impl<D: Droid> Explorer<D> {
+    pub fn new(mut droid: D) -> Result<Explorer<D>, AdventureError> {
+        let start = Self::expect_room(droid.wake())?;
+        let mut rooms = HashMap::new();
+        let here = start.name.clone();
+        rooms.insert(here.clone(), start);
+        Ok(Explorer {
+            droid,
+            here,
+            rooms,
+            links: HashMap::new(),
+            carrying: Vec::new(),
+            dangerous: Vec::new(),
+            checkpoint: None,
+            password: None,
+        })
+    }
+
+    fn expect_room(reply: Reply) -> Result<Room, AdventureError> {
+        match reply {
+            Reply::Prompt(text) => Room::parse_last(&text).ok_or(AdventureError::NoRoom(text)),
+            Reply::Halted(text) | Reply::Hung(text) => Err(AdventureError::Died(text)),
+        }
+    }
+
+    pub fn rooms(&self) -> &HashMap<String, Room> {
+        &self.rooms
+    }
+    pub fn carrying(&self) -> &[String] {
+        &self.carrying
+    }
+    pub fn dangerous(&self) -> &[String] {
+        &self.dangerous
+    }
+    pub fn checkpoint(&self) -> Option<&(String, Door)> {
+        self.checkpoint.as_ref()
+    }
+
+    fn walk(&mut self, door: Door) -> Result<Room, AdventureError> {
+        let room = Self::expect_room(self.droid.command(door.command()))?;
+        self.here = room.name.clone();
+        Ok(room)
+    }
+
+    /// Picks up an item, unless doing so on a snapshot kills, hangs or
+    /// strands the droid.
+    fn try_take(&mut self, item: &str) -> Result<(), AdventureError> {
+        let take = format!("take {}", item);
+        let mut trial = self.droid.clone();
+        let safe = match trial.command(&take) {
+            Reply::Prompt(_) => {
+                // Some things only bite once you try to leave
+                match self.rooms[&self.here].doors.first() {
+                    Some(door) => Self::expect_room(trial.command(door.command())).is_ok(),
+                    None => true,
+                }
+            }
+            _ => false,
+        };
+
+        if safe {
+            if let Reply::Prompt(_) = self.droid.command(&take) {
+                self.carrying.push(item.to_string());
+            } else {
+                return Err(AdventureError::Died(take));
+            }
+        } else {
+            self.dangerous.push(item.to_string());
+        }
+        Ok(())
+    }
+
+    /// Depth-first walk over the whole ship, grabbing everything safe on the
+    /// way, and ending up back where we started.
+    pub fn explore(&mut self) -> Result<(), AdventureError> {
+        self.visit(None)
+    }
+
+    fn visit(&mut self, came_by: Option<Door>) -> Result<(), AdventureError> {
+        let room = self.rooms[&self.here].clone();
+        for item in room.items.iter() {
+            self.try_take(item)?;
+        }
+
+        for door in room.doors {
+            if Some(door.opposite()) == came_by
+                || self.links.contains_key(&(room.name.clone(), door))
+                || self.checkpoint == Some((room.name.clone(), door))
+            {
+                continue;
+            }
+
+            let reply = self.droid.command(door.command());
+            if let Some(password) = find_password(reply.text()) {
+                // Happened to be carrying exactly the right things
+                self.password = Some(password);
+                return Ok(());
+            }
+            let next = Self::expect_room(reply)?;
+            self.here = next.name.clone();
+            if next.name == room.name {
+                // Bounced off the floor straight back to where we were
+                self.checkpoint = Some((room.name.clone(), door));
+                continue;
+            }
+
+            self.links
+                .insert((room.name.clone(), door), next.name.clone());
+            self.links
+                .insert((next.name.clone(), door.opposite()), room.name.clone());
+            if !self.rooms.contains_key(&next.name) {
+                self.rooms.insert(next.name.clone(), next);
+                self.visit(Some(door))?;
+                if self.password.is_some() {
+                    return Ok(());
+                }
+            }
+            self.walk(door.opposite())?;
+        }
+        Ok(())
+    }
+
+    /// Shortest route between two explored rooms
+    pub fn route(&self, from: &str, to: &str) -> Option<Vec<Door>> {
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::new();
+        queue.push_back((from.to_string(), Vec::new()));
+        seen.insert(from.to_string());
+        while let Some((name, path)) = queue.pop_front() {
+            if name == to {
+                return Some(path);
+            }
+            for door in self.rooms[&name].doors.iter() {
+                if let Some(next) = self.links.get(&(name.clone(), *door)) {
+                    if seen.insert(next.clone()) {
+                        let mut next_path = path.clone();
+                        next_path.push(*door);
+                        queue.push_back((next.clone(), next_path));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Heads to the checkpoint and tries every combination of the items we
+    /// picked up on the floor. Walks through them in Gray code order so that
+    /// each attempt only takes or drops a single item.
+    pub fn crack_floor(&mut self) -> Result<String, AdventureError> {
+        if let Some(password) = self.password.clone() {
+            return Ok(password);
+        }
+        let (checkpoint, floor) = self
+            .checkpoint
+            .clone()
+            .ok_or(AdventureError::NoCheckpoint)?;
+        let route = self
+            .route(&self.here, &checkpoint)
+            .ok_or(AdventureError::NoCheckpoint)?;
+        for door in route {
+            self.walk(door)?;
+        }
+
+        let items = self.carrying.clone();
+        for item in items.iter() {
+            if let Reply::Prompt(_) = self.droid.command(&format!("drop {}", item)) {
+                continue;
+            }
+            return Err(AdventureError::Died(format!("drop {}", item)));
+        }
+
+        let mut held = 0u64;
+        for i in 0..(1u64 << items.len()) {
+            let wanted = i ^ (i >> 1);
+            let changed = wanted ^ held;
+            if changed != 0 {
+                let idx = changed.trailing_zeros() as usize;
+                let verb = if wanted & changed != 0 {
+                    "take"
+                } else {
+                    "drop"
+                };
+                self.droid.command(&format!("{} {}", verb, items[idx]));
+                held = wanted;
+            }
+
+            let reply = self.droid.command(floor.command());
+            if let Some(password) = find_password(reply.text()) {
+                return Ok(password);
+            }
+            match reply {
+                Reply::Prompt(_) => {}
+                Reply::Halted(text) | Reply::Hung(text) => return Err(AdventureError::Died(text)),
+            }
+        }
+        Err(AdventureError::NoPassword)
+    }
+}
+
+/// Explore the whole ship, then find the right weight for the floor.
+pub fn solve<D: Droid>(droid: D) -> Result<String, AdventureError> {
+    let mut explorer = Explorer::new(droid)?;
+    explorer.explore()?;
+    explorer.crack_floor()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HULL_BREACH: &str = "
+
+
+== Hull Breach ==
+You got in through a hole in the floor here. To keep your ship from also freezing, the hole has been sealed.
+
+Doors here lead:
+- north
+- east
+- west
+
+Command?
+";
+
+    const EJECTED: &str = "
+
+
+== Pressure-Sensitive Floor ==
+Analyzing...
+
+Doors here lead:
+- west
+
+A loud, robotic voice says \"Alert! Droids on this ship are lighter than the detected value!\" and you are ejected back to the checkpoint.
+
+
+
+== Security Checkpoint ==
+In the next room, a pressure-sensitive floor will verify your identity.
+
+Doors here lead:
+- north
+- east
+
+Command?
+";
+
+    #[test]
+    fn parse_room() {
+        assert_eq!(
+            Room::parse_last(HULL_BREACH),
+            Some(Room {
+                name: "Hull Breach".to_string(),
+                doors: vec!(Door::North, Door::East, Door::West),
+                items: vec!(),
+            })
+        );
+        let room = Room::parse_last(
+            "== Kitchen ==\nSmells nice.\n\nDoors here lead:\n- south\n\nItems here:\n- mug\n- giant electromagnet\n\nCommand?\n",
+        )
+        .unwrap();
+        assert_eq!(room.doors, vec!(Door::South));
+        assert_eq!(room.items, vec!("mug", "giant electromagnet"));
+    }
+
+    #[test]
+    fn parse_ejected() {
+        let room = Room::parse_last(EJECTED).unwrap();
+        assert_eq!(room.name, "Security Checkpoint");
+        assert_eq!(room.doors, vec!(Door::North, Door::East));
+    }
+
+    /// Name, doors to other room indices, and items
+    type MockRoom = (&'static str, Vec<(Door, usize)>, Vec<&'static str>);
+
+    /// A tiny stand-in for the real ship, with the same kinds of traps
+    #[derive(Clone)]
+    struct MockShip {
+        here: usize,
+        rooms: Vec<MockRoom>,
+        inventory: Vec<&'static str>,
+        floor: (usize, Door),
+        weights: HashMap<&'static str, u32>,
+        target: u32,
+    }
+
+    impl MockShip {
+        fn new() -> MockShip {
+            // Hull(0) -n- Lab(1) -e- Checkpoint(2) -e- floor
+            //   |w                  |n
+            // Pantry(3)           Attic(4)
+            let rooms = vec![
+                (
+                    "Hull Breach",
+                    vec![(Door::North, 1), (Door::West, 3)],
+                    vec![],
+                ),
+                (
+                    "Lab",
+                    vec![(Door::South, 0), (Door::East, 2)],
+                    vec!["mug", "molten lava"],
+                ),
+                (
+                    "Security Checkpoint",
+                    vec![(Door::West, 1), (Door::North, 4)],
+                    vec![],
+                ),
+                (
+                    "Pantry",
+                    vec![(Door::East, 0)],
+                    vec!["sand", "infinite loop", "wreath"],
+                ),
+                (
+                    "Attic",
+                    vec![(Door::South, 2)],
+                    vec!["giant electromagnet", "coin"],
+                ),
+            ];
+            let weights = vec![("mug", 1), ("sand", 2), ("wreath", 4), ("coin", 8)];
+            MockShip {
+                here: 0,
+                rooms,
+                inventory: vec![],
+                floor: (2, Door::East),
+                weights: weights.into_iter().collect(),
+                target: 1 + 2 + 8,
+            }
+        }
+
+        fn describe(&self) -> String {
+            let (name, doors, items) = &self.rooms[self.here];
+            let mut out = format!("\n\n\n== {} ==\nIt's a room.\n\nDoors here lead:\n", name);
+            let mut all_doors: Vec<Door> = doors.iter().map(|d| d.0).collect();
+            if self.here == self.floor.0 {
+                all_doors.push(self.floor.1);
+            }
+            for door in all_doors {
+                out += &format!("- {}\n", door.command());
+            }
+            if !items.is_empty() {
+                out += "\nItems here:\n";
+                for item in items {
+                    out += &format!("- {}\n", item);
+                }
+            }
+            out + "\nCommand?\n"
+        }
+    }
+
+    impl Droid for MockShip {
+        fn wake(&mut self) -> Reply {
+            Reply::Prompt(self.describe())
+        }
+
+        fn command(&mut self, cmd: &str) -> Reply {
+            if let Some(item) = cmd.strip_prefix("take ") {
+                let items = &mut self.rooms[self.here].2;
+                let idx = items.iter().position(|i| *i == item).unwrap();
+                let item = items.remove(idx);
+                match item {
+                    "molten lava" => return Reply::Halted("You melt!".to_string()),
+                    "infinite loop" => {
+                        return Reply::Hung("You take the infinite loop.\n".repeat(10))
+                    }
+                    _ => self.inventory.push(item),
+                }
+                Reply::Prompt(format!("\nYou take the {}.\n\nCommand?\n", item))
+            } else if let Some(item) = cmd.strip_prefix("drop ") {
+                let idx = self.inventory.iter().position(|i| *i == item).unwrap();
+                let item = self.inventory.remove(idx);
+                self.rooms[self.here].2.push(item);
+                Reply::Prompt(format!("\nYou drop the {}.\n\nCommand?\n", item))
+            } else if let Some(door) = Door::from_word(cmd) {
+                if self.inventory.contains(&"giant electromagnet") {
+                    return Reply::Prompt(
+                        "The giant electromagnet is stuck to you.  You can't move!!\n\nCommand?\n"
+                            .to_string(),
+                    );
+                }
+                if (self.here, door) == self.floor {
+                    let weight: u32 = self.inventory.iter().map(|i| self.weights[i]).sum();
+                    if weight == self.target {
+                        return Reply::Halted("\"Oh, hello! You should be able to get in by typing 8675309 on the keypad at the main airlock.\"\n".to_string());
+                    }
+                    return Reply::Prompt(format!("\n\n\n== Pressure-Sensitive Floor ==\nAnalyzing...\n\nDoors here lead:\n- west\n\nYou are ejected back to the checkpoint.\n{}", self.describe()));
+                }
+                let link = self.rooms[self.here].1.iter().find(|d| d.0 == door);
+                match link {
+                    Some((_, next)) => {
+                        self.here = *next;
+                        Reply::Prompt(self.describe())
+                    }
+                    None => Reply::Prompt("\nYou can't go that way.\n\nCommand?\n".to_string()),
+                }
+            } else {
+                Reply::Prompt("\nUnrecognized command.\n\nCommand?\n".to_string())
+            }
+        }
+    }
+
+    #[test]
+    fn explore_mock() {
+        let mut explorer = Explorer::new(MockShip::new()).unwrap();
+        explorer.explore().unwrap();
+
+        assert_eq!(explorer.rooms().len(), 5);
+        assert_eq!(
+            explorer.checkpoint(),
+            Some(&("Security Checkpoint".to_string(), Door::East))
+        );
+        let mut carrying = explorer.carrying().to_vec();
+        carrying.sort();
+        assert_eq!(carrying, vec!("coin", "mug", "sand", "wreath"));
+        let mut dangerous = explorer.dangerous().to_vec();
+        dangerous.sort();
+        assert_eq!(
+            dangerous,
+            vec!("giant electromagnet", "infinite loop", "molten lava")
+        );
+        assert_eq!(
+            explorer.route("Pantry", "Attic"),
+            Some(vec!(Door::East, Door::North, Door::East, Door::North))
+        );
+    }
+
+    #[test]
+    fn solve_mock() {
+        assert_eq!(solve(MockShip::new()), Ok("8675309".to_string()));
+    }
+
+    #[test]
+    fn solve_mock_while_exploring() {
+        // By the time we first step on the floor we've got the mug and the
+        // coin, which happen to be enough
+        let mut ship = MockShip::new();
+        ship.target = 1 + 8;
+        let mut explorer = Explorer::new(ship).unwrap();
+        explorer.explore().unwrap();
+        assert_eq!(explorer.carrying(), ["mug", "coin"]);
+        assert_eq!(explorer.crack_floor(), Ok("8675309".to_string()));
+    }
+
+    #[test]
+    fn play_mock() {
+        let mut ship = MockShip::new();
+        let mut out = Vec::new();
+        play(&mut ship, "west\ntake sand\n".as_bytes(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("== Pantry =="));
+        assert!(out.ends_with("You take the sand.\n\nCommand?\n"));
+        assert_eq!(ship.inventory, vec!("sand"));
+    }
+
+    #[test]
+    fn intdroid_prompts() {
+        // Prints "Hi\n", then echoes one line back and stops
+        let cpu = IntMachine::new(vec![
+            104, 72, 104, 105, 104, 10, 3, 100, 4, 100, 1008, 100, 10, 101, 1006, 101, 6, 99,
+        ]);
+        let mut droid = IntDroid::new(cpu);
+        assert_eq!(droid.wake(), Reply::Prompt("Hi\n".to_string()));
+        assert_eq!(droid.command("yo"), Reply::Halted("yo\n".to_string()));
+
+        let mut droid = IntDroid::new(IntMachine::new(vec![1105, 1, 0]));
+        assert_eq!(droid.wake(), Reply::Hung(String::new()));
+    }
+}
@@ -0,0 +1,27 @@
+use aoc_1925::{play, solve, IntDroid};
+use intcode::IntMachine;
+use std::str::FromStr;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 2 {
+        eprintln!("Usage: {} <program> [--auto]", args[0]);
+        std::process::exit(1);
+    }
+
+    let program = std::fs::read_to_string(&args[1]).expect("Couldn't read program");
+    let mut droid = IntDroid::new(IntMachine::from_str(&program).expect("Bad program"));
+
+    if args.iter().any(|a| a == "--auto") {
+        match solve(droid) {
+            Ok(password) => println!("{}", password),
+            Err(e) => {
+                eprintln!("Failed: {:?}", e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        let stdin = std::io::stdin();
+        play(&mut droid, stdin.lock(), std::io::stdout()).expect("I/O error");
+    }
+}
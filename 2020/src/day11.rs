@@ -1,11 +1,8 @@
-#![allow(unused_variables, dead_code)]
+use arbgrid::{ArbGrid, Automaton, Cell, Coord2D, Grid as _, Neighborhood};
 
-use array2d::Array2D;
-use itertools::Itertools;
-use std::cmp::{max, min};
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 enum Space {
+    #[default]
     Floor,
     Empty,
     Occ,
@@ -22,164 +19,65 @@ impl Space {
     }
 }
 
+/// Floor is the default, so only seats get stored
 #[derive(Debug, Clone)]
 pub struct Content {
-    arr: Array2D<Space>,
-    changed: bool,
+    seats: ArbGrid<Space>,
 }
 
-impl Content {
-    fn surrounding_occ(&self, x: usize, y: usize) -> u8 {
-        let mut sum = 0;
-        for x_i in max(x as isize - 1, 0)..(min(self.arr.row_len(), x + 2) as isize) {
-            for y_i in max(y as isize - 1, 0)..(min(self.arr.column_len(), y + 2) as isize) {
-                let x_i = x_i as usize;
-                let y_i = y_i as usize;
-                if ((x_i, y_i) != (x, y)) && self.arr[(y_i, x_i)] == Space::Occ {
-                    sum += 1;
-                }
-            }
-        }
-        sum
-    }
-    fn surrounding_occ_pt2(&self, x: usize, y: usize) -> u8 {
-        let it = (-1isize..=1).cartesian_product(-1isize..=1);
-        let mut occ = 0;
-        for (dir_x, dir_y) in it {
-            if (dir_x, dir_y) == (0, 0) {
-                continue;
-            }
-
-            let (mut x_i, mut y_i): (isize, isize) = (x as isize + dir_x, y as isize + dir_y);
-
-            while (0isize..(self.arr.row_len() as isize)).contains(&x_i)
-                && (0isize..(self.arr.column_len() as isize)).contains(&y_i)
-            {
-                match self.arr[(y_i as usize, x_i as usize)] {
-                    Space::Occ => {
-                        occ += 1;
-                        break;
-                    }
-                    Space::Empty => {
-                        break;
-                    }
-                    _ => {}
-                }
-                x_i += dir_x;
-                y_i += dir_y;
-            }
-        }
-        occ
-    }
-
-    fn calc_tile_step(&self, x: usize, y: usize) -> Space {
-        match self.arr[(y, x)] {
-            Space::Empty => {
-                if self.surrounding_occ(x, y) == 0 {
-                    Space::Occ
-                } else {
-                    Space::Empty
-                }
-            }
-            Space::Occ => {
-                if self.surrounding_occ(x, y) >= 4 {
-                    Space::Empty
-                } else {
-                    Space::Occ
-                }
-            }
-            Space::Floor => Space::Floor,
-        }
-    }
-    fn calc_tile_step_pt2(&self, x: usize, y: usize) -> Space {
-        match self.arr[(y, x)] {
-            Space::Empty => {
-                if self.surrounding_occ_pt2(x, y) == 0 {
-                    Space::Occ
-                } else {
-                    Space::Empty
-                }
-            }
-            Space::Occ => {
-                if self.surrounding_occ_pt2(x, y) >= 5 {
-                    Space::Empty
-                } else {
-                    Space::Occ
-                }
-            }
-            Space::Floor => Space::Floor,
-        }
-    }
-    fn changed(&self) -> bool {
-        self.changed
-    }
-
-    fn step(&mut self) {
-        let mut new_arr = self.arr.clone();
-
-        for x in 0..self.arr.row_len() {
-            for y in 0..self.arr.column_len() {
-                new_arr[(y, x)] = self.calc_tile_step(x, y);
-            }
-        }
-
-        self.changed = self.arr != new_arr;
-        self.arr = new_arr;
-    }
-    fn step_pt2(&mut self) {
-        let mut new_arr = self.arr.clone();
-
-        for x in 0..self.arr.row_len() {
-            for y in 0..self.arr.column_len() {
-                new_arr[(y, x)] = self.calc_tile_step_pt2(x, y);
-            }
-        }
+fn occ_near(cell: &Cell<Space>) -> usize {
+    cell.count(|s| *s == Space::Occ)
+}
 
-        self.changed = self.arr != new_arr;
-        self.arr = new_arr;
-    }
+fn occ_in_sight(cell: &Cell<Space>) -> usize {
+    cell.grid()
+        .line_of_sight(cell.loc, Neighborhood::Moore, |_, s| *s != Space::Floor)
+        .filter(|(_, s)| **s == Space::Occ)
+        .count()
+}
 
-    fn seats_occ(&self) -> usize {
-        self.arr
-            .elements_row_major_iter()
-            .filter(|s| **s == Space::Occ)
-            .count()
-    }
+/// Runs the seats until nobody moves, with people leaving once `crowd` of
+/// the seats they can see are taken
+fn settle(input: &Content, crowd: usize, seen: impl Fn(&Cell<Space>) -> usize) -> usize {
+    let bounds = match input.seats.bounds() {
+        Some(b) => b,
+        None => return 0,
+    };
+    let mut seats = Automaton::bounded(input.seats.clone(), bounds, Neighborhood::Moore);
+    seats
+        .run_until_stable(
+            |cell| match (*cell.value, seen(cell)) {
+                (Space::Empty, 0) => Space::Occ,
+                (Space::Occ, n) if n >= crowd => Space::Empty,
+                (s, _) => s,
+            },
+            usize::MAX,
+        )
+        .expect("seats never settle");
+    seats
+        .grid()
+        .iter()
+        .filter(|(_, s)| **s == Space::Occ)
+        .count()
 }
 
 #[aoc_generator(day11)]
 pub fn input_generator(input: &str) -> Content {
-    let w = input.lines().next().unwrap().len();
-    let h = input.lines().count();
-
-    let gen = input
-        .lines()
-        .flat_map(|l| l.chars().map(|c| Space::from_char(c).unwrap()));
-
     Content {
-        arr: Array2D::from_iter_row_major(gen, h, w),
-        changed: true,
+        seats: ArbGrid::from_str_ogl(Coord2D(0, 0), input, &|c, _xy| {
+            Some(Space::from_char(c).unwrap())
+        }),
     }
 }
 
 #[aoc(day11, part1)]
 pub fn solve_part1(input: &Content) -> usize {
-    let mut grid = (*input).clone();
-
-    while grid.changed() {
-        grid.step();
-    }
-    grid.seats_occ()
+    settle(input, 4, occ_near)
 }
 
 #[aoc(day11, part2)]
 pub fn solve_part2(input: &Content) -> usize {
-    let mut grid = (*input).clone();
-
-    while grid.changed() {
-        grid.step_pt2();
-    }
-    grid.seats_occ()
+    settle(input, 5, occ_in_sight)
 }
 
 #[cfg(test)]
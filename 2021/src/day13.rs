@@ -91,8 +91,7 @@ pub fn solve_part1(input: &Content) -> usize {
     grid.iter().filter(|v| **v).count()
 }
 
-#[aoc(day13, part2)]
-pub fn solve_part2(input: &Content) -> usize {
+fn fold_all(input: &Content) -> BGrid {
     let range = input.range();
     let mut grid = BGrid::init(range.y, range.x, false);
 
@@ -100,20 +99,12 @@ pub fn solve_part2(input: &Content) -> usize {
     for f in &input.folds {
         fold(&mut grid, *f);
     }
+    grid
+}
 
-    // TODO: real unit test here.
-    for y in 0..grid.rows() {
-        for x in 0..grid.cols() {
-            if *grid.get(y, x).unwrap() {
-                print!("*");
-            } else {
-                print!(" ");
-            }
-        }
-        println!();
-    }
-
-    0
+#[aoc(day13, part2)]
+pub fn solve_part2(input: &Content) -> String {
+    aococr::read(&fold_all(input)).unwrap_or_else(|e| panic!("{}", e))
 }
 
 #[cfg(test)]
@@ -151,8 +142,10 @@ fold along x=5";
     }
     #[test]
     fn eg_part2() {
-        let content = input_generator(EG_INPUT);
-        assert_eq!(solve_part2(&content), 0);
+        // Just a square, not a letter
+        let grid = fold_all(&input_generator(EG_INPUT));
+        assert_eq!(grid.iter().filter(|v| **v).count(), 16);
+        assert_eq!(aococr::read(&grid), Err(aococr::OcrError::BadHeight(5)));
     }
     #[test]
     fn part1() {
@@ -162,6 +155,6 @@ fold along x=5";
     #[test]
     fn part2() {
         let content = input_generator(INPUT);
-        assert_eq!(solve_part2(&content), 0);
+        assert_eq!(solve_part2(&content), "BLKJRBAG");
     }
 }
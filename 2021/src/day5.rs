@@ -1,11 +1,8 @@
 #![allow(unused_variables, dead_code, non_camel_case_types)]
 
-use std::cmp::Ordering;
-use std::cmp::{max, min};
-
 use vek::vec::vec2::Vec2;
 
-use grid::Grid;
+use arbgrid::{ArbGrid, Coord2D};
 use scan_fmt::scan_fmt;
 
 type gridsize = i32;
@@ -45,61 +42,22 @@ impl Line {
     fn is_vert(&self) -> bool {
         self.start.x == self.end.x
     }
-
-    fn bounds(lines: &[Self]) -> Self {
-        let min_x = lines.iter().map(|l| min(l.start.x, l.end.x)).min().unwrap();
-        let max_x = lines.iter().map(|l| max(l.start.x, l.end.x)).max().unwrap();
-        let min_y = lines.iter().map(|l| min(l.start.y, l.end.y)).min().unwrap();
-        let max_y = lines.iter().map(|l| max(l.start.y, l.end.y)).max().unwrap();
-
-        Line {
-            start: Vec2::new(min_x, min_y),
-            end: Vec2::new(max_x, max_y),
-        }
-    }
-
-    fn sub(self, c: Coord) -> Self {
-        Self {
-            start: self.start - c,
-            end: self.end - c,
-        }
-    }
 }
 
 #[derive(Clone)]
 pub struct Content {
     lines: Vec<Line>,
-    grid: Grid<griddata>,
-    grid_start: Coord,
+    grid: ArbGrid<griddata>,
 }
 
 impl Content {
     fn draw_line(&mut self, l: &Line) {
-        let l = l.clone().sub(self.grid_start);
-        let step: Coord = Coord {
-            x: match l.start.x.cmp(&l.end.x) {
-                Ordering::Less => 1,
-                Ordering::Equal => 0,
-                Ordering::Greater => -1,
-            },
-            y: match l.start.y.cmp(&l.end.y) {
-                Ordering::Less => 1,
-                Ordering::Equal => 0,
-                Ordering::Greater => -1,
-            },
-        };
-        let mut cur_pt = l.start;
-        let end = l.end + step; // dangling to get the last one.
-        loop {
-            *self
-                .grid
-                .get_mut(cur_pt.y as usize, cur_pt.x as usize)
-                .unwrap() += 1;
-            cur_pt += step;
-            if cur_pt == end {
-                break;
-            }
-        }
+        self.grid.draw_line(
+            Coord2D(l.start.x, l.start.y),
+            Coord2D(l.end.x, l.end.y),
+            1,
+            |a, b| a + b,
+        );
     }
 }
 
@@ -107,17 +65,9 @@ impl Content {
 pub fn input_generator(input: &str) -> Content {
     // Meaning the drawn lines, not each line of the file :P
     let lines: Vec<Line> = input.lines().map(|l| Line::from_str(l).unwrap()).collect();
-    let bounds = Line::bounds(&lines);
     Content {
         lines,
-        grid: Grid::new(
-            (bounds.end.y + 1 - bounds.start.y) as usize,
-            (bounds.end.x + 1 - bounds.start.x) as usize,
-        ),
-        grid_start: Coord {
-            x: bounds.start.x,
-            y: bounds.start.y,
-        },
+        grid: ArbGrid::new(0),
     }
 }
 
@@ -130,7 +80,7 @@ pub fn solve_part1(input: &Content) -> usize {
             input.draw_line(line);
         }
     }
-    input.grid.iter().filter(|v| **v >= 2).count()
+    input.grid.iter().filter(|(_, v)| **v >= 2).count()
 }
 
 #[aoc(day5, part2)]
@@ -140,7 +90,7 @@ pub fn solve_part2(input: &Content) -> usize {
     for line in lines.iter() {
         input.draw_line(line);
     }
-    input.grid.iter().filter(|v| **v >= 2).count()
+    input.grid.iter().filter(|(_, v)| **v >= 2).count()
 }
 
 #[cfg(test)]
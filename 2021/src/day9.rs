@@ -1,5 +1,6 @@
 #![allow(unused_variables, dead_code)]
 
+use arbgrid::{ArbGrid, Coord2D, Grid as _, Neighborhood};
 use grid::Grid;
 
 pub struct Content {
@@ -48,24 +49,18 @@ impl Content {
         items
     }
 
-    fn find_pool_size_for_point(&self, x: usize, y: usize) -> usize {
-        let mut filled = Grid::init(self.grid.rows(), self.grid.cols(), false);
-
-        self.fill_from_point(x, y, &mut filled);
-
-        filled.iter().filter(|v| **v).count()
-    }
-
-    fn fill_from_point(&self, x: usize, y: usize, mask: &mut Grid<bool>) {
-        if *mask.get(y, x).unwrap() || (*self.grid.get(y, x).unwrap() == 9) {
-            return;
-        }
-
-        *mask.get_mut(y, x).unwrap() = true;
-
-        for (px, py) in self.adjacent(x, y) {
-            self.fill_from_point(px, py, mask);
+    /// Sizes of every basin, biggest first. Every spot but a 9 is in
+    /// exactly one.
+    fn basin_sizes(&self) -> Vec<usize> {
+        let mut heights: ArbGrid<u8> = ArbGrid::new(9);
+        for x in 0..self.grid.cols() {
+            for y in 0..self.grid.rows() {
+                heights.insert(Coord2D(x as i32, y as i32), *self.grid.get(y, x).unwrap());
+            }
         }
+        heights
+            .components(Neighborhood::VonNeumann, |_, h| *h != 9)
+            .sizes()
     }
 }
 
@@ -93,23 +88,7 @@ pub fn solve_part1(input: &Content) -> usize {
 
 #[aoc(day9, part2)]
 pub fn solve_part2(input: &Content) -> usize {
-    let mut pool_sizes: Vec<usize> = input
-        .find_mins()
-        .iter()
-        .map(|(x, y)| input.find_pool_size_for_point(*x, *y))
-        .collect();
-
-    pool_sizes.sort_unstable();
-
-    let mut res = 1;
-
-    for _ in 0..3 {
-        if let Some(biggest) = pool_sizes.pop() {
-            res *= biggest;
-        }
-    }
-
-    res
+    input.basin_sizes().iter().take(3).product()
 }
 
 #[cfg(test)]
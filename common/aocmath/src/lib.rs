@@ -0,0 +1,222 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Index, IndexMut, Mul, Neg, Rem, Sub, SubAssign};
+
+/// Euclid's two-thousand-year-old algorithm for finding the greatest common
+/// divisor.
+/// Taken from the rust standard library docs :)
+/// ```
+/// assert_eq!(aocmath::gcd(1, 10), 1);
+/// assert_eq!(aocmath::gcd(2, 10), 2);
+/// ```
+pub fn gcd<T: PartialEq + Rem<Output = T> + Default + Copy>(x: T, y: T) -> T {
+    let mut x = x;
+    let mut y = y;
+    while y != T::default() {
+        let t = y;
+        y = x % y;
+        x = t;
+    }
+    x
+}
+
+/// Least common multiple, dividing first so it doesn't overflow any earlier
+/// than it has to.
+/// ```
+/// assert_eq!(aocmath::lcm(4, 6), 12);
+/// assert_eq!(aocmath::lcm(18, 28), 252);
+/// assert_eq!(aocmath::lcm(0, 5), 0);
+/// ```
+pub fn lcm<T>(x: T, y: T) -> T
+where
+    T: PartialEq + Rem<Output = T> + Div<Output = T> + Mul<Output = T> + Default + Copy,
+{
+    if x == T::default() || y == T::default() {
+        return T::default();
+    }
+    x / gcd(x, y) * y
+}
+
+/// `a * b mod m`, going through i128 so big moduli don't overflow. Always
+/// returns something in `0..m`, even for negative inputs.
+/// ```
+/// assert_eq!(aocmath::mulmod(-3, 4, 10), 8);
+/// assert_eq!(aocmath::mulmod(1 << 62, 1 << 62, 1_000_000_007), 829_977_023);
+/// ```
+pub fn mulmod(a: i64, b: i64, m: i64) -> i64 {
+    ((a as i128 * b as i128).rem_euclid(m as i128)) as i64
+}
+
+/// `base ^ exp mod m` by repeated squaring
+/// ```
+/// assert_eq!(aocmath::modpow(3, 4, 7), 4);
+/// assert_eq!(aocmath::modpow(2, 0, 7), 1);
+/// ```
+pub fn modpow(base: i64, exp: u64, m: i64) -> i64 {
+    let mut result = 1 % m;
+    let mut base = base.rem_euclid(m);
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mulmod(result, base, m);
+        }
+        base = mulmod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Multiplicative inverse mod `m`, if there is one. Uses the extended
+/// Euclidean algorithm, so `m` doesn't need to be prime.
+/// ```
+/// assert_eq!(aocmath::modinv(3, 7), Some(5));
+/// assert_eq!(aocmath::modinv(-3, 7), Some(2));
+/// assert_eq!(aocmath::modinv(2, 4), None);
+/// ```
+pub fn modinv(a: i64, m: i64) -> Option<i64> {
+    let (mut old_r, mut r) = (a.rem_euclid(m) as i128, m as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    while r != 0 {
+        let q = old_r / r;
+        let next_r = old_r - q * r;
+        old_r = r;
+        r = next_r;
+        let next_s = old_s - q * s;
+        old_s = s;
+        s = next_s;
+    }
+    if old_r != 1 {
+        return None;
+    }
+    Some(old_s.rem_euclid(m as i128) as i64)
+}
+
+/// A plain 3D integer vector
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Vec3(pub i64, pub i64, pub i64);
+
+impl Vec3 {
+    pub const ZERO: Vec3 = Vec3(0, 0, 0);
+
+    pub fn new(x: i64, y: i64, z: i64) -> Self {
+        Vec3(x, y, z)
+    }
+
+    /// Sum of the absolute values of each component
+    pub fn manhattan(self) -> i64 {
+        self.0.abs() + self.1.abs() + self.2.abs()
+    }
+
+    pub fn signum(self) -> Self {
+        Vec3(self.0.signum(), self.1.signum(), self.2.signum())
+    }
+}
+
+impl Index<usize> for Vec3 {
+    type Output = i64;
+    fn index(&self, axis: usize) -> &i64 {
+        match axis {
+            0 => &self.0,
+            1 => &self.1,
+            2 => &self.2,
+            _ => panic!("Vec3 has no axis {}", axis),
+        }
+    }
+}
+
+impl IndexMut<usize> for Vec3 {
+    fn index_mut(&mut self, axis: usize) -> &mut i64 {
+        match axis {
+            0 => &mut self.0,
+            1 => &mut self.1,
+            2 => &mut self.2,
+            _ => panic!("Vec3 has no axis {}", axis),
+        }
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+    fn add(self, other: Vec3) -> Vec3 {
+        Vec3(self.0 + other.0, self.1 + other.1, self.2 + other.2)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+    fn sub(self, other: Vec3) -> Vec3 {
+        Vec3(self.0 - other.0, self.1 - other.1, self.2 - other.2)
+    }
+}
+
+impl Mul<i64> for Vec3 {
+    type Output = Vec3;
+    fn mul(self, scale: i64) -> Vec3 {
+        Vec3(self.0 * scale, self.1 * scale, self.2 * scale)
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+    fn neg(self) -> Vec3 {
+        Vec3(-self.0, -self.1, -self.2)
+    }
+}
+
+impl AddAssign for Vec3 {
+    fn add_assign(&mut self, other: Vec3) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Vec3 {
+    fn sub_assign(&mut self, other: Vec3) {
+        *self = *self - other;
+    }
+}
+
+impl fmt::Display for Vec3 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<x={}, y={}, z={}>", self.0, self.1, self.2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lcm_chain() {
+        assert_eq!(lcm(lcm(18i64, 28), 44), 2772);
+        assert_eq!(lcm(lcm(2028u64, 5898), 4702), 4686774924);
+    }
+
+    #[test]
+    fn modular() {
+        const M: i64 = 119_315_717_514_047;
+        for a in [1, 2, 12345, M - 1, 98_765_432_123].iter() {
+            let inv = modinv(*a, M).unwrap();
+            assert_eq!(mulmod(*a, inv, M), 1);
+            // Fermat, since M is prime
+            assert_eq!(modpow(*a, (M - 2) as u64, M), inv);
+        }
+        assert_eq!(modpow(5, 1, 1), 0);
+    }
+
+    #[test]
+    fn vec_ops() {
+        let a = Vec3(1, -2, 3);
+        let b = Vec3(4, 5, -6);
+        assert_eq!(a + b, Vec3(5, 3, -3));
+        assert_eq!(a - b, Vec3(-3, -7, 9));
+        assert_eq!(-a * 2, Vec3(-2, 4, -6));
+        assert_eq!((b - a).signum(), Vec3(1, 1, -1));
+        assert_eq!(a.manhattan(), 6);
+        assert_eq!(a[2], 3);
+
+        let mut c = a;
+        c += b;
+        c[0] = 0;
+        assert_eq!(c, Vec3(0, 3, -3));
+        assert_eq!(c.to_string(), "<x=0, y=3, z=-3>");
+    }
+}
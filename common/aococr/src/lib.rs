@@ -0,0 +1,319 @@
+use arbgrid::{ArbGrid, Coord2D, Grid as _};
+use array2d::Array2D;
+use std::fmt;
+use std::fmt::Debug;
+
+/// Letters 4 wide (give or take) and 6 tall, which most years use
+const SMALL_FONT: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+/// Letters 6 wide and 10 tall, from 2018 day 10
+const LARGE_FONT: &[(char, &str)] = &[
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+/// Anything that can be read as a picture, with (0, 0) at the top left
+pub trait Pixels {
+    /// Width and height
+    fn dims(&self) -> (usize, usize);
+    fn lit(&self, x: usize, y: usize) -> bool;
+}
+
+/// An `ArbGrid` with y going down, like `from_str_ogl`. Cells that aren't
+/// the grid's default are lit, whatever that default is, so the default
+/// has to be the "off" value. A grid defaulting to something like
+/// "transparent" would read every pixel it stores as lit.
+impl<T> Pixels for ArbGrid<T>
+where
+    T: Clone + Debug + PartialEq,
+{
+    fn dims(&self) -> (usize, usize) {
+        match self.bounds() {
+            Some((lo, hi)) => ((hi.0 - lo.0 + 1) as usize, (hi.1 - lo.1 + 1) as usize),
+            None => (0, 0),
+        }
+    }
+    fn lit(&self, x: usize, y: usize) -> bool {
+        let (lo, _) = self.bounds().unwrap();
+        self.get(Coord2D(lo.0 + x as i32, lo.1 + y as i32)) != self.default_value()
+    }
+}
+
+/// Reads a picture upside down, for grids with y going up like `from_str`
+pub struct FlipY<'a, P>(pub &'a P);
+
+impl<P: Pixels> Pixels for FlipY<'_, P> {
+    fn dims(&self) -> (usize, usize) {
+        self.0.dims()
+    }
+    fn lit(&self, x: usize, y: usize) -> bool {
+        self.0.lit(x, self.0.dims().1 - 1 - y)
+    }
+}
+
+/// 1 is lit, like the 2019 day 8 image format
+impl Pixels for Array2D<u8> {
+    fn dims(&self) -> (usize, usize) {
+        (self.num_columns(), self.num_rows())
+    }
+    fn lit(&self, x: usize, y: usize) -> bool {
+        self.get(y, x) == Some(&1)
+    }
+}
+
+impl Pixels for grid::Grid<bool> {
+    fn dims(&self) -> (usize, usize) {
+        (self.cols(), self.rows())
+    }
+    fn lit(&self, x: usize, y: usize) -> bool {
+        self.get(y, x) == Some(&true)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    /// Nothing is lit
+    Blank,
+    /// The lit part isn't as tall as either font
+    BadHeight(usize),
+    /// Doesn't look like any letter we know; `glyph` is drawn with '#' and '.'
+    UnknownGlyph { index: usize, glyph: String },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OcrError::Blank => write!(f, "nothing to read"),
+            OcrError::BadHeight(h) => {
+                write!(f, "text is {} pixels tall, which matches no font", h)
+            }
+            OcrError::UnknownGlyph { index, glyph } => {
+                write!(f, "unrecognized letter #{}:\n{}", index, glyph)
+            }
+        }
+    }
+}
+
+impl std::error::Error for OcrError {}
+
+/// Reads the capital letters in a picture. Letters are told apart by the
+/// blank columns between them, and the font is picked by how tall the lit
+/// part of the picture is.
+pub fn read(pic: &impl Pixels) -> Result<String, OcrError> {
+    let (w, h) = pic.dims();
+    let rows: Vec<usize> = (0..h).filter(|y| (0..w).any(|x| pic.lit(x, *y))).collect();
+    let (top, bottom) = match (rows.first(), rows.last()) {
+        (Some(t), Some(b)) => (*t, *b),
+        _ => return Err(OcrError::Blank),
+    };
+    let height = bottom - top + 1;
+    let font = match height {
+        6 => SMALL_FONT,
+        10 => LARGE_FONT,
+        _ => return Err(OcrError::BadHeight(height)),
+    };
+
+    let column = |x: usize| -> Vec<bool> { (top..=bottom).map(|y| pic.lit(x, y)).collect() };
+    let mut glyphs: Vec<Vec<Vec<bool>>> = Vec::new();
+    let mut current = Vec::new();
+    for x in 0..w {
+        let col = column(x);
+        if col.iter().any(|b| *b) {
+            current.push(col);
+        } else if !current.is_empty() {
+            glyphs.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        glyphs.push(current);
+    }
+
+    glyphs
+        .iter()
+        .enumerate()
+        .map(|(index, cols)| {
+            let glyph = (0..height)
+                .map(|y| {
+                    cols.iter()
+                        .map(|col| if col[y] { '#' } else { '.' })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            font.iter()
+                .find(|(_, art)| *art == glyph)
+                .map(|(c, _)| *c)
+                .ok_or(OcrError::UnknownGlyph { index, glyph })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn art(s: &str) -> ArbGrid<bool> {
+        ArbGrid::from_str_ogl(Coord2D(0, 0), s, &|c, _xy| match c {
+            '#' => Some(true),
+            _ => None,
+        })
+    }
+
+    /// Draws each letter from the font, side by side with a gap between
+    fn spell(font: &[(char, &str)], word: &str) -> String {
+        let glyphs: Vec<&str> = word
+            .chars()
+            .map(|c| font.iter().find(|(l, _)| *l == c).unwrap().1)
+            .collect();
+        let height = glyphs[0].lines().count();
+        (0..height)
+            .map(|y| {
+                glyphs
+                    .iter()
+                    .map(|g| g.lines().nth(y).unwrap())
+                    .collect::<Vec<_>>()
+                    .join(".")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn small_font() {
+        let all: String = SMALL_FONT.iter().map(|(c, _)| *c).collect();
+        assert_eq!(read(&art(&spell(SMALL_FONT, &all))), Ok(all));
+    }
+
+    #[test]
+    fn large_font() {
+        let all: String = LARGE_FONT.iter().map(|(c, _)| *c).collect();
+        assert_eq!(read(&art(&spell(LARGE_FONT, &all))), Ok(all));
+
+        // Padding around the outside doesn't matter
+        let mut pic = Array2D::filled_with(0u8, 12, 22);
+        for (y, row) in spell(LARGE_FONT, "HNZ").lines().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c == '#' {
+                    pic.set(y + 1, x + 1, 1).unwrap();
+                }
+            }
+        }
+        assert_eq!(read(&pic), Ok("HNZ".to_string()));
+    }
+
+    #[test]
+    fn flipped_and_grid() {
+        let pic = art("#..#.####\n#..#....#\n####...#.\n#..#..#..\n#..#.#...\n#..#.####");
+        assert_eq!(read(&pic), Ok("HZ".to_string()));
+        assert!(read(&FlipY(&pic)).is_err());
+
+        let mut g = grid::Grid::init(6, 4, false);
+        for (y, row) in ".##.\n#..#\n#...\n#...\n#..#\n.##.".lines().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                *g.get_mut(y, x).unwrap() = c == '#';
+            }
+        }
+        assert_eq!(read(&g), Ok("C".to_string()));
+
+        // Unset cells are off even when the default isn't `T::default()`
+        let mut inverted = ArbGrid::new(true);
+        for (y, row) in ".##.\n#..#\n#...\n#...\n#..#\n.##.".lines().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c == '#' {
+                    inverted.insert(Coord2D(x as i32, y as i32), false);
+                }
+            }
+        }
+        assert_eq!(read(&inverted), Ok("C".to_string()));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(read(&art("....")), Err(OcrError::Blank));
+        assert_eq!(read(&art("#\n#\n#")), Err(OcrError::BadHeight(3)));
+        assert_eq!(
+            read(&art("#####\n#...#\n#...#\n#...#\n#...#\n#####")),
+            Err(OcrError::UnknownGlyph {
+                index: 0,
+                glyph: "#####\n#...#\n#...#\n#...#\n#...#\n#####".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use arbgrid::{ArbGrid, Coord2D};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const MONSTER: &str = "                  # \n#    ##    ##    ###\n #  #  #  #  #  #   ";
+
+fn monster() -> ArbGrid<bool> {
+    ArbGrid::from_str_ogl(Coord2D(0, 0), MONSTER, &|c, _xy| match c {
+        '#' => Some(true),
+        _ => None,
+    })
+}
+
+/// About the size of an assembled 2020 day 20 image: noise, with a few
+/// monsters hidden in it, some of them turned around
+fn sea() -> ArbGrid<bool> {
+    let mut state: u32 = 0x2020;
+    let mut sea = ArbGrid::new(false);
+    for y in 0..96 {
+        for x in 0..96 {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            sea.insert(Coord2D(x, y), (state >> 16) % 10 < 3);
+        }
+    }
+    let turned = monster().orientations();
+    for (i, at) in [(3, 5), (40, 10), (10, 60), (60, 70), (70, 30)]
+        .iter()
+        .enumerate()
+    {
+        let m = &turned[i * 3 % 8];
+        let lo = m.bounds().unwrap().0;
+        sea.paste(m, Coord2D(at.0 - lo.0, at.1 - lo.1));
+    }
+    sea
+}
+
+fn bench_monsters(c: &mut Criterion) {
+    let sea = sea();
+    let monster = monster();
+    let found = sea.find_pattern_any_orientation(&monster, |p, s| p == s);
+    assert!(found.len() >= 5, "lost some monsters: {:?}", found);
+    c.bench_function("sea monsters 96x96 x8 orientations", |b| {
+        b.iter(|| black_box(&sea).find_pattern_any_orientation(&monster, |p, s| p == s))
+    });
+}
+
+criterion_group!(benches, bench_monsters);
+criterion_main!(benches);
@@ -0,0 +1,233 @@
+use crate::{ArbGrid, Coord, Coord2D, Neighborhood};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// Which cells an `Automaton` updates each generation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Extent<C> {
+    /// Every cell in the box, with nothing outside it
+    Bounded(C, C),
+    /// Every cell in the box, with opposite faces joined
+    Toroidal(C, C),
+    /// Everything that isn't the default, and whatever touches it
+    Sparse,
+}
+
+/// One cell as a rule sees it, with the grid as it was before this step
+pub struct Cell<'a, T, C: Coord = Coord2D> {
+    pub loc: C,
+    pub value: &'a T,
+    grid: &'a ArbGrid<T, C>,
+    hood: Neighborhood,
+    extent: Extent<C>,
+}
+
+impl<'a, T, C> Cell<'a, T, C>
+where
+    T: Clone + Debug + PartialEq,
+    C: Coord,
+{
+    /// The neighboring cells, respecting the automaton's edges
+    pub fn neighbors(&self) -> impl Iterator<Item = (C, &'a T)> + 'a {
+        let loc = self.loc;
+        let extent = self.extent;
+        let grid = self.grid;
+        self.hood
+            .directions()
+            .into_iter()
+            .filter_map(move |dir: C| {
+                let next = loc.offset(dir);
+                match extent {
+                    Extent::Bounded(lo, hi) if !next.within(lo, hi) => None,
+                    Extent::Toroidal(lo, hi) => Some(next.wrap(lo, hi)),
+                    _ => Some(next),
+                }
+            })
+            .map(move |xy| (xy, grid.get(xy)))
+    }
+
+    /// How many neighbors match `pred`
+    pub fn count(&self, pred: impl Fn(&T) -> bool) -> usize {
+        self.neighbors().filter(|(_, v)| pred(v)).count()
+    }
+
+    /// The whole previous generation, for rules that look further than the
+    /// neighborhood
+    pub fn grid(&self) -> &'a ArbGrid<T, C> {
+        self.grid
+    }
+}
+
+/// Where a run of an `Automaton` started repeating itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// First generation that shows up again later
+    pub start: usize,
+    /// Generations until it comes back; 1 for a fixed point
+    pub period: usize,
+}
+
+/// Runs a cellular automaton over an `ArbGrid`, one generation at a time.
+/// Only cells that differ from the grid's default are stored, so rules for
+/// sparse grids must turn a default cell with all-default neighbors into
+/// the default, or the grid would fill all of space.
+#[derive(Debug, Clone)]
+pub struct Automaton<T, C = Coord2D> {
+    grid: ArbGrid<T, C>,
+    back: ArbGrid<T, C>,
+    hood: Neighborhood,
+    extent: Extent<C>,
+    generation: usize,
+}
+
+impl<T, C> Automaton<T, C>
+where
+    T: Clone + Debug + PartialEq,
+    C: Coord,
+{
+    fn with_extent(grid: ArbGrid<T, C>, hood: Neighborhood, extent: Extent<C>) -> Self {
+        let mut grid = grid;
+        grid.trim();
+        let back = ArbGrid::new(grid.default.clone());
+        Automaton {
+            grid,
+            back,
+            hood,
+            extent,
+            generation: 0,
+        }
+    }
+
+    /// Updates every cell between `lo` and `hi`, and treats anything outside
+    /// as not there at all
+    pub fn bounded(grid: ArbGrid<T, C>, (lo, hi): (C, C), hood: Neighborhood) -> Self {
+        Self::with_extent(grid, hood, Extent::Bounded(lo, hi))
+    }
+
+    /// Like `bounded`, except that neighbors wrap around the edges
+    pub fn toroidal(grid: ArbGrid<T, C>, (lo, hi): (C, C), hood: Neighborhood) -> Self {
+        Self::with_extent(grid, hood, Extent::Toroidal(lo, hi))
+    }
+
+    /// Lets the grid grow without limit
+    pub fn sparse(grid: ArbGrid<T, C>, hood: Neighborhood) -> Self {
+        Self::with_extent(grid, hood, Extent::Sparse)
+    }
+
+    pub fn grid(&self) -> &ArbGrid<T, C> {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> ArbGrid<T, C> {
+        self.grid
+    }
+
+    /// How many steps have been taken
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    fn candidates(&self) -> Vec<C> {
+        match self.extent {
+            Extent::Bounded(lo, hi) | Extent::Toroidal(lo, hi) => C::box_iter(lo, hi),
+            Extent::Sparse => {
+                let mut cells: Vec<C> = self
+                    .grid
+                    .iter()
+                    .flat_map(|(loc, _)| {
+                        let mut near = self.hood.directions();
+                        for dir in near.iter_mut() {
+                            *dir = loc.offset(*dir);
+                        }
+                        near.push(*loc);
+                        near
+                    })
+                    .collect();
+                cells.sort_unstable();
+                cells.dedup();
+                cells
+            }
+        }
+    }
+
+    /// Moves forward one generation, returning whether anything changed
+    pub fn step(&mut self, mut rule: impl FnMut(&Cell<T, C>) -> T) -> bool {
+        let default = self.grid.default.clone();
+        let mut changed = false;
+        self.back.clear();
+        for loc in self.candidates() {
+            let value = self.grid.get(loc);
+            let cell = Cell {
+                loc,
+                value,
+                grid: &self.grid,
+                hood: self.hood,
+                extent: self.extent,
+            };
+            let next = rule(&cell);
+            changed |= next != *value;
+            if next != default {
+                self.back.insert(loc, next);
+            }
+        }
+        std::mem::swap(&mut self.grid, &mut self.back);
+        self.generation += 1;
+        changed
+    }
+
+    /// Steps until nothing changes, giving the generation that first showed
+    /// the final layout. Gives up with `None` after `limit` steps.
+    pub fn run_until_stable(
+        &mut self,
+        mut rule: impl FnMut(&Cell<T, C>) -> T,
+        limit: usize,
+    ) -> Option<usize> {
+        for _ in 0..limit {
+            if !self.step(&mut rule) {
+                return Some(self.generation - 1);
+            }
+        }
+        None
+    }
+
+    /// Steps until some layout comes back, leaving the grid on the first
+    /// repeat. Gives up with `None` after `limit` steps.
+    pub fn find_cycle(&mut self, rule: impl FnMut(&Cell<T, C>) -> T, limit: usize) -> Option<Cycle>
+    where
+        T: Hash + Eq,
+    {
+        self.find_cycle_by(
+            rule,
+            |grid| {
+                grid.iter()
+                    .map(|(c, v)| (*c, v.clone()))
+                    .collect::<Vec<_>>()
+            },
+            limit,
+        )
+    }
+
+    /// Like `find_cycle`, but only remembers `key` of each layout, e.g. a
+    /// bitmask, which is much cheaper than a copy of the whole grid. Two
+    /// layouts with the same key count as the same.
+    pub fn find_cycle_by<K: Hash + Eq>(
+        &mut self,
+        mut rule: impl FnMut(&Cell<T, C>) -> T,
+        key: impl Fn(&ArbGrid<T, C>) -> K,
+        limit: usize,
+    ) -> Option<Cycle> {
+        let mut seen: HashMap<K, usize> = HashMap::new();
+        seen.insert(key(&self.grid), self.generation);
+        for _ in 0..limit {
+            self.step(&mut rule);
+            if let Some(start) = seen.insert(key(&self.grid), self.generation) {
+                return Some(Cycle {
+                    start,
+                    period: self.generation - start,
+                });
+            }
+        }
+        None
+    }
+}
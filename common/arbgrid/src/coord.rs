@@ -0,0 +1,286 @@
+use crate::Direction;
+use std::cmp::{max, min};
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A point on an integer lattice of some fixed number of dimensions.
+///
+/// Everything dimension-specific lives in `axis` and `from_axes`; bounds and
+/// neighbor enumeration are built on top of those.
+pub trait Coord: Copy + Debug + Ord + Hash {
+    const DIMS: usize;
+
+    /// The component along axis `i`, where `i < DIMS`
+    fn axis(self, i: usize) -> i32;
+
+    /// Builds a coordinate by asking for each axis in turn
+    fn from_axes(f: impl FnMut(usize) -> i32) -> Self;
+
+    fn bound_min(self, other: Self) -> Self {
+        Self::from_axes(|i| min(self.axis(i), other.axis(i)))
+    }
+    fn bound_max(self, other: Self) -> Self {
+        Self::from_axes(|i| max(self.axis(i), other.axis(i)))
+    }
+
+    fn offset(self, by: Self) -> Self {
+        Self::from_axes(|i| self.axis(i) + by.axis(i))
+    }
+
+    fn origin() -> Self {
+        Self::from_axes(|_| 0)
+    }
+
+    /// Brings this back into the box between `lo` and `hi` as if opposite
+    /// faces were joined
+    fn wrap(self, lo: Self, hi: Self) -> Self {
+        Self::from_axes(|i| {
+            let span = hi.axis(i) - lo.axis(i) + 1;
+            lo.axis(i) + (self.axis(i) - lo.axis(i)).rem_euclid(span)
+        })
+    }
+
+    /// Whether this lies in the box between `lo` and `hi`, inclusive
+    fn within(self, lo: Self, hi: Self) -> bool {
+        (0..Self::DIMS).all(|i| lo.axis(i) <= self.axis(i) && self.axis(i) <= hi.axis(i))
+    }
+
+    /// Steps between the two, moving along one axis at a time
+    fn manhattan(self, other: Self) -> i32 {
+        (0..Self::DIMS)
+            .map(|i| (self.axis(i) - other.axis(i)).abs())
+            .sum()
+    }
+
+    /// Steps between the two, where diagonal moves are allowed
+    fn chebyshev(self, other: Self) -> i32 {
+        (0..Self::DIMS)
+            .map(|i| (self.axis(i) - other.axis(i)).abs())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The 2*DIMS coordinates one step away along a single axis
+    fn orthogonal(self) -> Vec<Self> {
+        let mut out = Vec::with_capacity(2 * Self::DIMS);
+        for axis in 0..Self::DIMS {
+            for step in [-1, 1].iter() {
+                out.push(Self::from_axes(|i| {
+                    self.axis(i) + if i == axis { *step } else { 0 }
+                }));
+            }
+        }
+        out
+    }
+
+    /// All 3^DIMS - 1 coordinates touching this one, diagonals included
+    fn surrounding(self) -> Vec<Self> {
+        let count = 3usize.pow(Self::DIMS as u32);
+        (0..count)
+            .filter(|n| *n != count / 2) // The all-zeroes offset is ourselves
+            .map(|n| Self::from_axes(|i| self.axis(i) + (n / 3usize.pow(i as u32) % 3) as i32 - 1))
+            .collect()
+    }
+
+    /// Every coordinate in the box between `lo` and `hi`, inclusive
+    fn box_iter(lo: Self, hi: Self) -> Vec<Self> {
+        let mut out = vec![lo];
+        for axis in 0..Self::DIMS {
+            out = out
+                .into_iter()
+                .flat_map(|c| {
+                    (lo.axis(axis)..=hi.axis(axis))
+                        .map(move |v| Self::from_axes(|i| if i == axis { v } else { c.axis(i) }))
+                })
+                .collect();
+        }
+        out
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
+pub struct Coord2D(pub i32, pub i32);
+
+impl Coord2D {
+    pub fn new(x: i32, y: i32) -> Self {
+        Coord2D(x, y)
+    }
+
+    pub fn up(self, count: i32) -> Self {
+        Coord2D(self.0, self.1 + count)
+    }
+    pub fn down(self, count: i32) -> Self {
+        Coord2D(self.0, self.1 - count)
+    }
+    pub fn left(self, count: i32) -> Self {
+        Coord2D(self.0 - count, self.1)
+    }
+    pub fn right(self, count: i32) -> Self {
+        Coord2D(self.0 + count, self.1)
+    }
+
+    /// `count` steps in `dir`, with up being +y
+    pub fn step(self, dir: Direction, count: i32) -> Self {
+        self + dir.offset() * count
+    }
+
+    pub fn manhattan(self, other: Self) -> i32 {
+        Coord::manhattan(self, other)
+    }
+
+    /// A quarter turn clockwise around the origin, with up being +y
+    pub fn rotate_cw(self) -> Self {
+        Coord2D(self.1, -self.0)
+    }
+
+    /// A quarter turn counterclockwise around the origin, with up being +y
+    pub fn rotate_ccw(self) -> Self {
+        Coord2D(-self.1, self.0)
+    }
+}
+
+impl Coord for Coord2D {
+    const DIMS: usize = 2;
+
+    fn axis(self, i: usize) -> i32 {
+        match i {
+            0 => self.0,
+            1 => self.1,
+            _ => panic!("Coord2D has no axis {}", i),
+        }
+    }
+    fn from_axes(mut f: impl FnMut(usize) -> i32) -> Self {
+        Coord2D(f(0), f(1))
+    }
+
+    // Up, down, left, right
+    fn orthogonal(self) -> Vec<Self> {
+        vec![self.up(1), self.down(1), self.left(1), self.right(1)]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
+pub struct Coord3D(pub i32, pub i32, pub i32);
+
+impl Coord3D {
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        Coord3D(x, y, z)
+    }
+
+    /// Just the x and y
+    pub fn flatten(self) -> Coord2D {
+        Coord2D(self.0, self.1)
+    }
+}
+
+impl From<Coord2D> for Coord3D {
+    fn from(xy: Coord2D) -> Self {
+        Coord3D(xy.0, xy.1, 0)
+    }
+}
+
+impl Coord for Coord3D {
+    const DIMS: usize = 3;
+
+    fn axis(self, i: usize) -> i32 {
+        match i {
+            0 => self.0,
+            1 => self.1,
+            2 => self.2,
+            _ => panic!("Coord3D has no axis {}", i),
+        }
+    }
+    fn from_axes(mut f: impl FnMut(usize) -> i32) -> Self {
+        Coord3D(f(0), f(1), f(2))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
+pub struct Coord4D(pub i32, pub i32, pub i32, pub i32);
+
+impl Coord4D {
+    pub fn new(x: i32, y: i32, z: i32, w: i32) -> Self {
+        Coord4D(x, y, z, w)
+    }
+}
+
+impl From<Coord2D> for Coord4D {
+    fn from(xy: Coord2D) -> Self {
+        Coord4D(xy.0, xy.1, 0, 0)
+    }
+}
+
+impl From<Coord3D> for Coord4D {
+    fn from(xyz: Coord3D) -> Self {
+        Coord4D(xyz.0, xyz.1, xyz.2, 0)
+    }
+}
+
+impl Coord for Coord4D {
+    const DIMS: usize = 4;
+
+    fn axis(self, i: usize) -> i32 {
+        match i {
+            0 => self.0,
+            1 => self.1,
+            2 => self.2,
+            3 => self.3,
+            _ => panic!("Coord4D has no axis {}", i),
+        }
+    }
+    fn from_axes(mut f: impl FnMut(usize) -> i32) -> Self {
+        Coord4D(f(0), f(1), f(2), f(3))
+    }
+}
+
+/// Componentwise arithmetic, and scaling by an integer
+macro_rules! coord_ops {
+    ($name:ident, $($field:tt),+) => {
+        impl Add for $name {
+            type Output = Self;
+            fn add(self, other: Self) -> Self {
+                $name($(self.$field + other.$field),+)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+            fn sub(self, other: Self) -> Self {
+                $name($(self.$field - other.$field),+)
+            }
+        }
+
+        impl Mul<i32> for $name {
+            type Output = Self;
+            fn mul(self, k: i32) -> Self {
+                $name($(self.$field * k),+)
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+            fn neg(self) -> Self {
+                $name($(-self.$field),+)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+    };
+}
+
+pub(crate) use coord_ops;
+
+coord_ops!(Coord2D, 0, 1);
+coord_ops!(Coord3D, 0, 1, 2);
+coord_ops!(Coord4D, 0, 1, 2, 3);
@@ -0,0 +1,310 @@
+use crate::{ArbGrid, Coord, Coord2D, Grid};
+use std::fmt::Debug;
+
+/// Like `ArbGrid`, but the cells live in one flat `Vec` covering a box that
+/// grows as cells get set outside it. Lookups are just arithmetic, which
+/// pays off for grids that are mostly full; for scattered points across a
+/// huge area, stick with `ArbGrid`.
+#[derive(Debug, Clone)]
+pub struct DenseGrid<T, C = Coord2D> {
+    /// Row-major over `alloc`, with the last axis varying fastest so the
+    /// order matches `C`'s. `None` is a cell that was never set.
+    cells: Vec<Option<T>>,
+    default: T,
+    alloc: Option<(C, C)>,
+    min: Option<C>,
+    max: Option<C>,
+}
+
+/// Cells in the box between `lo` and `hi`
+fn volume<C: Coord>(lo: C, hi: C) -> usize {
+    (0..C::DIMS)
+        .map(|i| (hi.axis(i) - lo.axis(i) + 1) as usize)
+        .product()
+}
+
+/// Where `loc` sits in a box between `lo` and `hi` it's known to be within
+fn index<C: Coord>(loc: C, lo: C, hi: C) -> usize {
+    (0..C::DIMS).fold(0, |idx, i| {
+        let span = (hi.axis(i) - lo.axis(i) + 1) as usize;
+        idx * span + (loc.axis(i) - lo.axis(i)) as usize
+    })
+}
+
+/// The inverse of `index`
+fn coord_at<C: Coord>(idx: usize, lo: C, hi: C) -> C {
+    C::from_axes(|i| {
+        let stride: usize = ((i + 1)..C::DIMS)
+            .map(|j| (hi.axis(j) - lo.axis(j) + 1) as usize)
+            .product();
+        let span = (hi.axis(i) - lo.axis(i) + 1) as usize;
+        lo.axis(i) + (idx / stride % span) as i32
+    })
+}
+
+impl<T, C> DenseGrid<T, C>
+where
+    T: Clone + Debug + PartialEq,
+    C: Coord,
+{
+    pub fn new(default: T) -> DenseGrid<T, C> {
+        DenseGrid {
+            cells: Vec::new(),
+            default,
+            alloc: None,
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Sets aside room for everything between `lo` and `hi` up front, so
+    /// filling that in never has to grow
+    pub fn with_bounds(default: T, lo: C, hi: C) -> DenseGrid<T, C> {
+        DenseGrid {
+            cells: vec![None; volume(lo, hi)],
+            alloc: Some((lo, hi)),
+            ..DenseGrid::new(default)
+        }
+    }
+
+    fn slot(&self, loc: C) -> Option<usize> {
+        let (lo, hi) = self.alloc?;
+        if loc.within(lo, hi) {
+            Some(index(loc, lo, hi))
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, loc: C) -> &T {
+        self.slot(loc)
+            .and_then(|i| self.cells[i].as_ref())
+            .unwrap_or(&self.default)
+    }
+
+    pub fn get_mut(&mut self, loc: C) -> &mut T {
+        let idx = match self.slot(loc) {
+            Some(i) if self.cells[i].is_some() => i,
+            _ => {
+                self.insert(loc, self.default.clone());
+                self.slot(loc).unwrap()
+            }
+        };
+        self.cells[idx].as_mut().unwrap()
+    }
+
+    /// Makes room for `loc`. Grows by at least the current size along each
+    /// axis that needs it, so a run of inserts marching outward doesn't
+    /// re-copy everything every time.
+    fn grow_to(&mut self, loc: C) {
+        let (lo, hi) = match self.alloc {
+            None => {
+                self.cells = vec![None];
+                self.alloc = Some((loc, loc));
+                return;
+            }
+            Some((lo, hi)) if loc.within(lo, hi) => return,
+            Some(b) => b,
+        };
+        let new_lo = C::from_axes(|i| {
+            let span = hi.axis(i) - lo.axis(i) + 1;
+            if loc.axis(i) < lo.axis(i) {
+                loc.axis(i).min(lo.axis(i) - span)
+            } else {
+                lo.axis(i)
+            }
+        });
+        let new_hi = C::from_axes(|i| {
+            let span = hi.axis(i) - lo.axis(i) + 1;
+            if loc.axis(i) > hi.axis(i) {
+                loc.axis(i).max(hi.axis(i) + span)
+            } else {
+                hi.axis(i)
+            }
+        });
+
+        let mut cells = vec![None; volume(new_lo, new_hi)];
+        for (i, cell) in std::mem::take(&mut self.cells).into_iter().enumerate() {
+            if cell.is_some() {
+                cells[index(coord_at(i, lo, hi), new_lo, new_hi)] = cell;
+            }
+        }
+        self.cells = cells;
+        self.alloc = Some((new_lo, new_hi));
+    }
+
+    pub fn insert(&mut self, loc: C, value: T) -> Option<T> {
+        self.grow_to(loc);
+        if let Some(min) = self.min {
+            self.min = Some(min.bound_min(loc));
+        } else {
+            self.min = Some(loc);
+        }
+        if let Some(max) = self.max {
+            self.max = Some(max.bound_max(loc));
+        } else {
+            self.max = Some(loc);
+        }
+        let idx = self.slot(loc).unwrap();
+        self.cells[idx].replace(value)
+    }
+
+    /// Takes `loc` back out, shrinking the bounds if it was on the edge
+    pub fn remove(&mut self, loc: C) -> Option<T> {
+        let idx = self.slot(loc)?;
+        let old = self.cells[idx].take()?;
+        if let Some((lo, hi)) = self.bounds() {
+            if (0..C::DIMS).any(|i| loc.axis(i) == lo.axis(i) || loc.axis(i) == hi.axis(i)) {
+                self.fit_bounds();
+            }
+        }
+        Some(old)
+    }
+
+    /// Keeps only the cells where `keep` is true
+    pub fn retain(&mut self, mut keep: impl FnMut(C, &T) -> bool) {
+        if let Some((lo, hi)) = self.alloc {
+            for (i, cell) in self.cells.iter_mut().enumerate() {
+                if cell.as_ref().is_some_and(|v| !keep(coord_at(i, lo, hi), v)) {
+                    *cell = None;
+                }
+            }
+        }
+        self.fit_bounds();
+    }
+
+    /// Forgets every cell holding the default. Keeps the room it had.
+    pub fn trim(&mut self) {
+        let default = self.default.clone();
+        self.retain(|_, v| *v != default);
+    }
+
+    /// Shrinks the bounds back down to just what's stored
+    fn fit_bounds(&mut self) {
+        let mut min: Option<C> = None;
+        let mut max: Option<C> = None;
+        for (loc, _) in self.iter() {
+            min = Some(min.map_or(loc, |m| m.bound_min(loc)));
+            max = Some(max.map_or(loc, |m| m.bound_max(loc)));
+        }
+        self.min = min;
+        self.max = max;
+    }
+
+    /// Forgets every cell, leaving only the default. Keeps the room it had.
+    pub fn clear(&mut self) {
+        for cell in self.cells.iter_mut() {
+            *cell = None;
+        }
+        self.min = None;
+        self.max = None;
+    }
+
+    pub fn bounds(&self) -> Option<(C, C)> {
+        Some((self.min?, self.max?))
+    }
+
+    /// Every cell that was set, in the same order `ArbGrid` gives them
+    pub fn iter(&self) -> impl Iterator<Item = (C, &T)> + '_ {
+        let alloc = self.alloc;
+        self.cells.iter().enumerate().filter_map(move |(i, cell)| {
+            let (lo, hi) = alloc?;
+            Some((coord_at(i, lo, hi), cell.as_ref()?))
+        })
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (C, &mut T)> + '_ {
+        let alloc = self.alloc;
+        self.cells
+            .iter_mut()
+            .enumerate()
+            .filter_map(move |(i, cell)| {
+                let (lo, hi) = alloc?;
+                Some((coord_at(i, lo, hi), cell.as_mut()?))
+            })
+    }
+}
+
+impl<T, C> Grid<T, C> for DenseGrid<T, C>
+where
+    T: Clone + Debug + PartialEq,
+    C: Coord,
+{
+    fn default_value(&self) -> &T {
+        &self.default
+    }
+    fn get(&self, loc: C) -> &T {
+        DenseGrid::get(self, loc)
+    }
+    fn get_mut(&mut self, loc: C) -> &mut T {
+        DenseGrid::get_mut(self, loc)
+    }
+    fn insert(&mut self, loc: C, value: T) -> Option<T> {
+        DenseGrid::insert(self, loc, value)
+    }
+    fn bounds(&self) -> Option<(C, C)> {
+        DenseGrid::bounds(self)
+    }
+    fn cells<'a>(&'a self) -> impl Iterator<Item = (C, &'a T)>
+    where
+        T: 'a,
+    {
+        self.iter()
+    }
+}
+
+/// Like `ArbGrid`, cells holding the default don't count, and neither does
+/// how much room each has set aside
+impl<T, C> PartialEq for DenseGrid<T, C>
+where
+    T: Clone + Debug + PartialEq,
+    C: Coord,
+{
+    fn eq(&self, other: &Self) -> bool {
+        fn set<T, C>(grid: &DenseGrid<T, C>) -> Vec<(C, &T)>
+        where
+            T: Clone + Debug + PartialEq,
+            C: Coord,
+        {
+            grid.iter().filter(|(_, v)| **v != grid.default).collect()
+        }
+        self.default == other.default && set(self) == set(other)
+    }
+}
+
+impl<T, C> Eq for DenseGrid<T, C>
+where
+    T: Clone + Debug + Eq,
+    C: Coord,
+{
+}
+
+impl<T, C> From<&ArbGrid<T, C>> for DenseGrid<T, C>
+where
+    T: Clone + Debug + PartialEq,
+    C: Coord,
+{
+    fn from(grid: &ArbGrid<T, C>) -> Self {
+        let mut out = match grid.bounds() {
+            Some((lo, hi)) => DenseGrid::with_bounds(grid.default_value().clone(), lo, hi),
+            None => DenseGrid::new(grid.default_value().clone()),
+        };
+        for (loc, v) in grid.iter() {
+            out.insert(*loc, v.clone());
+        }
+        out
+    }
+}
+
+impl<T, C> From<&DenseGrid<T, C>> for ArbGrid<T, C>
+where
+    T: Clone + Debug + PartialEq,
+    C: Coord,
+{
+    fn from(grid: &DenseGrid<T, C>) -> Self {
+        let mut out = ArbGrid::new(grid.default.clone());
+        for (loc, v) in grid.iter() {
+            out.insert(loc, v.clone());
+        }
+        out
+    }
+}
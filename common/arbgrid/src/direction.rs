@@ -0,0 +1,133 @@
+use crate::Coord2D;
+use std::str::FromStr;
+
+/// One of the eight ways to step on a grid, going clockwise from up. Up is
+/// +y, like `Coord2D::up`; for grids where y goes down use `offset_ogl`.
+/// North, east, south and west are up, right, down and left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// Every direction, clockwise from up
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    /// Just the four along an axis, clockwise from up
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    fn index(self) -> i32 {
+        self as i32
+    }
+
+    fn from_index(i: i32) -> Self {
+        Direction::ALL[i.rem_euclid(8) as usize]
+    }
+
+    /// Reads `U/D/L/R`, `N/S/E/W` or `^v<>`, in either case
+    pub fn from_char(c: char) -> Option<Direction> {
+        match c.to_ascii_uppercase() {
+            'U' | 'N' | '^' => Some(Direction::Up),
+            'D' | 'S' | 'V' => Some(Direction::Down),
+            'L' | 'W' | '<' => Some(Direction::Left),
+            'R' | 'E' | '>' => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    /// A quarter turn right
+    pub fn turn_cw(self) -> Self {
+        Direction::from_index(self.index() + 2)
+    }
+
+    /// A quarter turn left
+    pub fn turn_ccw(self) -> Self {
+        Direction::from_index(self.index() - 2)
+    }
+
+    pub fn reverse(self) -> Self {
+        Direction::from_index(self.index() + 4)
+    }
+
+    /// Turns right by `degrees` (left if negative), which has to be a
+    /// multiple of 45
+    pub fn turn(self, degrees: i32) -> Self {
+        assert_eq!(degrees % 45, 0, "Can't turn {} degrees", degrees);
+        Direction::from_index(self.index() + degrees / 45)
+    }
+
+    /// One step this way
+    pub fn offset(self) -> Coord2D {
+        let (dx, dy) = match self {
+            Direction::Up => (0, 1),
+            Direction::UpRight => (1, 1),
+            Direction::Right => (1, 0),
+            Direction::DownRight => (1, -1),
+            Direction::Down => (0, -1),
+            Direction::DownLeft => (-1, -1),
+            Direction::Left => (-1, 0),
+            Direction::UpLeft => (-1, 1),
+        };
+        Coord2D(dx, dy)
+    }
+
+    /// One step this way, on a grid where y goes down like `from_str_ogl`
+    pub fn offset_ogl(self) -> Coord2D {
+        let Coord2D(dx, dy) = self.offset();
+        Coord2D(dx, -dy)
+    }
+}
+
+/// Takes anything `from_char` does, and also two-letter diagonals like
+/// `NE`, `UL` or `v>`
+impl FromStr for Direction {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let dirs: Vec<Direction> = s
+            .trim()
+            .chars()
+            .map(Direction::from_char)
+            .collect::<Option<_>>()
+            .ok_or(())?;
+        match dirs[..] {
+            [d] => Ok(d),
+            [a, b] => {
+                let (a, b) = if a < b { (a, b) } else { (b, a) };
+                match (a, b) {
+                    (Direction::Up, Direction::Right) => Ok(Direction::UpRight),
+                    (Direction::Right, Direction::Down) => Ok(Direction::DownRight),
+                    (Direction::Down, Direction::Left) => Ok(Direction::DownLeft),
+                    (Direction::Up, Direction::Left) => Ok(Direction::UpLeft),
+                    _ => Err(()),
+                }
+            }
+            _ => Err(()),
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::coord::coord_ops;
+use crate::Coord;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A cell on a hex grid, in axial coordinates (q, r). The third cube
+/// coordinate is always `-q - r`, so it isn't stored.
+///
+/// Both neighborhoods are the same six cells, so anything built on `Coord`
+/// (grids, automata, pathfinding) works on hexes unchanged. Which way is
+/// "north" depends on the `HexLayout` the directions were read with.
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
+pub struct Hex(pub i32, pub i32);
+
+impl Hex {
+    pub fn new(q: i32, r: i32) -> Self {
+        Hex(q, r)
+    }
+
+    /// As cube coordinates (x, y, z), which always add up to 0
+    pub fn cube(self) -> (i32, i32, i32) {
+        (self.0, self.1, -self.0 - self.1)
+    }
+
+    pub fn from_cube(x: i32, y: i32, z: i32) -> Self {
+        debug_assert_eq!(x + y + z, 0, "cube coordinates must add up to 0");
+        Hex(x, y)
+    }
+
+    /// Steps between the two, moving to a neighbor each time
+    pub fn distance(self, other: Self) -> i32 {
+        let (x, y, z) = (self - other).cube();
+        (x.abs() + y.abs() + z.abs()) / 2
+    }
+
+    /// The six cells touching this one, going around
+    pub fn neighbors(self) -> [Hex; 6] {
+        [
+            self + Hex(1, 0),
+            self + Hex(1, -1),
+            self + Hex(0, -1),
+            self + Hex(-1, 0),
+            self + Hex(-1, 1),
+            self + Hex(0, 1),
+        ]
+    }
+}
+
+impl Coord for Hex {
+    const DIMS: usize = 2;
+
+    fn axis(self, i: usize) -> i32 {
+        match i {
+            0 => self.0,
+            1 => self.1,
+            _ => panic!("Hex has no axis {}", i),
+        }
+    }
+    fn from_axes(mut f: impl FnMut(usize) -> i32) -> Self {
+        Hex(f(0), f(1))
+    }
+
+    fn manhattan(self, other: Self) -> i32 {
+        self.distance(other)
+    }
+    fn chebyshev(self, other: Self) -> i32 {
+        self.distance(other)
+    }
+    fn orthogonal(self) -> Vec<Self> {
+        self.neighbors().to_vec()
+    }
+    fn surrounding(self) -> Vec<Self> {
+        self.neighbors().to_vec()
+    }
+}
+
+coord_ops!(Hex, 0, 1);
+
+/// Which way the hexes are turned, which decides what the compass
+/// directions mean
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexLayout {
+    /// Rows of hexes, with neighbors `e, se, sw, w, nw, ne`
+    PointyTop,
+    /// Columns of hexes, with neighbors `n, ne, se, s, sw, nw`
+    FlatTop,
+}
+
+impl HexLayout {
+    /// Each direction's name and the step it takes
+    pub fn directions(self) -> [(&'static str, Hex); 6] {
+        match self {
+            HexLayout::PointyTop => [
+                ("e", Hex(1, 0)),
+                ("se", Hex(0, 1)),
+                ("sw", Hex(-1, 1)),
+                ("w", Hex(-1, 0)),
+                ("nw", Hex(0, -1)),
+                ("ne", Hex(1, -1)),
+            ],
+            HexLayout::FlatTop => [
+                ("n", Hex(0, -1)),
+                ("ne", Hex(1, -1)),
+                ("se", Hex(1, 0)),
+                ("s", Hex(0, 1)),
+                ("sw", Hex(-1, 1)),
+                ("nw", Hex(-1, 0)),
+            ],
+        }
+    }
+
+    /// The step for one direction, in any case
+    pub fn parse_dir(self, s: &str) -> Option<Hex> {
+        let s = s.trim().to_ascii_lowercase();
+        self.directions()
+            .iter()
+            .find(|(name, _)| *name == s)
+            .map(|(_, step)| *step)
+    }
+
+    /// Reads a walk, either comma separated (`ne,ne,s`) or run together
+    /// (`esenee`), into its steps
+    pub fn parse_walk(self, s: &str) -> Option<Vec<Hex>> {
+        let s = s.trim();
+        if s.contains(',') {
+            return s.split(',').map(|d| self.parse_dir(d)).collect();
+        }
+        let mut out = Vec::new();
+        let mut rest = s;
+        while !rest.is_empty() {
+            // Two-letter names first, so "ne" isn't read as "n" then "e"
+            let (step, len) = rest
+                .get(..2)
+                .and_then(|d| self.parse_dir(d))
+                .map(|step| (step, 2))
+                .or_else(|| Some((self.parse_dir(rest.get(..1)?)?, 1)))?;
+            out.push(step);
+            rest = &rest[len..];
+        }
+        Some(out)
+    }
+
+    /// Where a walk from `start` ends up
+    pub fn walk(self, start: Hex, s: &str) -> Option<Hex> {
+        Some(
+            self.parse_walk(s)?
+                .into_iter()
+                .fold(start, |here, step| here + step),
+        )
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{ArbGrid, Coord2D};
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+pub type Rgb = [u8; 3];
+
+/// Which way is up when turning a grid into a picture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    /// Larger y is higher up, like `to_string`
+    YUp,
+    /// Larger y is further down, like `to_string_ogl`
+    YDown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageOptions {
+    /// Pixels per cell, along each side
+    pub scale: usize,
+    pub orientation: Orientation,
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        ImageOptions {
+            scale: 1,
+            orientation: Orientation::YUp,
+        }
+    }
+}
+
+impl ImageOptions {
+    pub fn new(scale: usize, orientation: Orientation) -> Self {
+        ImageOptions { scale, orientation }
+    }
+}
+
+impl<T> ArbGrid<T, Coord2D>
+where
+    T: Clone + Debug + PartialEq,
+{
+    /// Colors of every cell in the bounds, one row at a time from the top
+    /// of the picture, along with the width and height in cells
+    fn color_rows(
+        &self,
+        opts: ImageOptions,
+        color_fn: &dyn Fn(&T, Coord2D) -> Rgb,
+    ) -> (usize, usize, Vec<Vec<Rgb>>) {
+        let (lo, hi) = match self.bounds() {
+            Some(b) => b,
+            None => return (0, 0, Vec::new()),
+        };
+        let mut ys: Vec<i32> = (lo.1..=hi.1).collect();
+        if opts.orientation == Orientation::YUp {
+            ys.reverse();
+        }
+        let rows: Vec<Vec<Rgb>> = ys
+            .into_iter()
+            .map(|y| {
+                (lo.0..=hi.0)
+                    .map(|x| {
+                        let loc = Coord2D(x, y);
+                        color_fn(self.get(loc), loc)
+                    })
+                    .collect()
+            })
+            .collect();
+        ((hi.0 - lo.0 + 1) as usize, rows.len(), rows)
+    }
+
+    /// Raw RGB bytes with each cell blown up to `scale` pixels square
+    fn raster(
+        &self,
+        opts: ImageOptions,
+        color_fn: &dyn Fn(&T, Coord2D) -> Rgb,
+    ) -> (usize, usize, Vec<u8>) {
+        let (w, h, rows) = self.color_rows(opts, color_fn);
+        let mut out = Vec::with_capacity(w * h * opts.scale * opts.scale * 3);
+        for row in rows.iter() {
+            for _ in 0..opts.scale {
+                for rgb in row.iter() {
+                    for _ in 0..opts.scale {
+                        out.extend_from_slice(rgb);
+                    }
+                }
+            }
+        }
+        (w * opts.scale, h * opts.scale, out)
+    }
+
+    /// Binary PPM, which needs nothing but a header in front of the pixels
+    pub fn write_ppm(
+        &self,
+        mut out: impl Write,
+        opts: ImageOptions,
+        color_fn: &dyn Fn(&T, Coord2D) -> Rgb,
+    ) -> io::Result<()> {
+        let (w, h, data) = self.raster(opts, color_fn);
+        write!(out, "P6\n{} {}\n255\n", w, h)?;
+        out.write_all(&data)
+    }
+
+    #[cfg(feature = "png")]
+    pub fn write_png(
+        &self,
+        out: impl Write,
+        opts: ImageOptions,
+        color_fn: &dyn Fn(&T, Coord2D) -> Rgb,
+    ) -> io::Result<()> {
+        let (w, h, data) = self.raster(opts, color_fn);
+        let mut encoder = png::Encoder::new(out, w as u32, h as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let to_io = |e: png::EncodingError| io::Error::new(io::ErrorKind::InvalidInput, e);
+        let mut writer = encoder.write_header().map_err(to_io)?;
+        writer.write_image_data(&data).map_err(to_io)
+    }
+
+    /// SVG with one rectangle per run of same-colored cells in a row, so it
+    /// stays sharp at any size. `scale` only sets the default display size.
+    pub fn write_svg(
+        &self,
+        mut out: impl Write,
+        opts: ImageOptions,
+        color_fn: &dyn Fn(&T, Coord2D) -> Rgb,
+    ) -> io::Result<()> {
+        let (w, h, rows) = self.color_rows(opts, color_fn);
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" shape-rendering="crispEdges">"#,
+            w * opts.scale,
+            h * opts.scale,
+            w,
+            h
+        )?;
+        for (y, row) in rows.iter().enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                let run = row[x..].iter().take_while(|c| **c == row[x]).count();
+                let [r, g, b] = row[x];
+                writeln!(
+                    out,
+                    r##"<rect x="{}" y="{}" width="{}" height="1" fill="#{:02x}{:02x}{:02x}"/>"##,
+                    x, y, run, r, g, b
+                )?;
+                x += run;
+            }
+        }
+        writeln!(out, "</svg>")
+    }
+
+    /// Writes to `path` in whichever format its extension names: `ppm`,
+    /// `svg`, or (with the `png` feature) `png`
+    pub fn save_image(
+        &self,
+        path: impl AsRef<Path>,
+        opts: ImageOptions,
+        color_fn: &dyn Fn(&T, Coord2D) -> Rgb,
+    ) -> io::Result<()> {
+        let path = path.as_ref();
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        let out = || File::create(path).map(BufWriter::new);
+        match ext.as_deref() {
+            Some("ppm") => self.write_ppm(out()?, opts, color_fn),
+            Some("svg") => self.write_svg(out()?, opts, color_fn),
+            #[cfg(feature = "png")]
+            Some("png") => self.write_png(out()?, opts, color_fn),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Don't know how to save {}", path.display()),
+            )),
+        }
+    }
+}
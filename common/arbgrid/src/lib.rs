@@ -1,49 +1,222 @@
-use std::cmp::{max, min};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Debug;
 
-#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq)]
-pub struct Coord2D(pub i32, pub i32);
+mod automaton;
+mod coord;
+mod dense;
+mod direction;
+mod hex;
+mod image;
+mod line;
+mod path;
+mod pattern;
+mod region;
+mod sight;
+mod terminal;
+mod transform;
+pub use automaton::{Automaton, Cell, Cycle};
+pub use coord::{Coord, Coord2D, Coord3D, Coord4D};
+pub use dense::DenseGrid;
+pub use direction::Direction;
+pub use hex::{Hex, HexLayout};
+pub use image::{ImageOptions, Orientation, Rgb};
+pub use line::{parse_path, polyline, LinePoints};
+pub use path::{shortest_path, Paths};
+pub use pattern::PatternMatch;
+pub use region::{Components, Region};
+pub use sight::{in_sight, points_between, sweep, visible_from, Heading};
+pub use terminal::{Asciicast, Renderer, Style};
 
-impl Coord2D {
-    fn bound_max(self, other: Self) -> Self {
-        Coord2D(max(self.0, other.0), max(self.1, other.1))
+/// Which cells count as next to each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// Only one step along a single axis
+    VonNeumann,
+    /// Anything touching, diagonals included
+    Moore,
+}
+
+impl Neighborhood {
+    /// The offsets from a cell to its neighbors
+    pub fn directions<C: Coord>(self) -> Vec<C> {
+        match self {
+            Neighborhood::VonNeumann => C::origin().orthogonal(),
+            Neighborhood::Moore => C::origin().surrounding(),
+        }
+    }
+}
+
+/// What happens to neighbors that fall outside the grid's bounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edges {
+    /// Keep them, they get the default value
+    Unbounded,
+    /// Drop them
+    Clip,
+    /// Wrap around to the other side
+    Wrap,
+}
+
+/// What algorithms need from a grid, so they work the same whichever way
+/// the cells are stored
+pub trait Grid<T, C: Coord = Coord2D>
+where
+    T: Clone + Debug + PartialEq,
+{
+    /// What every cell that was never set holds
+    fn default_value(&self) -> &T;
+    fn get(&self, loc: C) -> &T;
+    fn get_mut(&mut self, loc: C) -> &mut T;
+    fn insert(&mut self, loc: C, value: T) -> Option<T>;
+    fn bounds(&self) -> Option<(C, C)>;
+    /// Every cell that was set
+    fn cells<'a>(&'a self) -> impl Iterator<Item = (C, &'a T)>
+    where
+        T: 'a;
+
+    /// The neighbors of `loc`, with what happens at the bounds set by `edges`.
+    /// Wrapping to the same cell more than once (on a very thin grid) gives
+    /// it more than once.
+    fn neighbors<'a>(
+        &'a self,
+        loc: C,
+        hood: Neighborhood,
+        edges: Edges,
+    ) -> impl Iterator<Item = (C, &'a T)>
+    where
+        T: 'a,
+    {
+        let bounds = self.bounds();
+        hood.directions()
+            .into_iter()
+            .filter_map(move |dir: C| {
+                let next = loc.offset(dir);
+                match (edges, bounds) {
+                    (Edges::Unbounded, _) => Some(next),
+                    (Edges::Clip, Some((lo, hi))) if next.within(lo, hi) => Some(next),
+                    (Edges::Wrap, Some((lo, hi))) => Some(next.wrap(lo, hi)),
+                    _ => None,
+                }
+            })
+            .map(move |xy| (xy, self.get(xy)))
+    }
+
+    /// Looks outward from `loc` in each direction of the neighborhood, giving
+    /// the first cell where `stop` matches. Directions that reach the bounds
+    /// first give nothing.
+    fn line_of_sight<'a>(
+        &'a self,
+        loc: C,
+        hood: Neighborhood,
+        stop: impl Fn(C, &T) -> bool,
+    ) -> impl Iterator<Item = (C, &'a T)>
+    where
+        T: 'a,
+    {
+        let bounds = self.bounds();
+        hood.directions().into_iter().filter_map(move |dir: C| {
+            let (lo, hi) = bounds?;
+            let mut next = loc.offset(dir);
+            while next.within(lo, hi) {
+                let val = self.get(next);
+                if stop(next, val) {
+                    return Some((next, val));
+                }
+                next = next.offset(dir);
+            }
+            None
+        })
     }
-    fn bound_min(self, other: Self) -> Self {
-        Coord2D(min(self.0, other.0), min(self.1, other.1))
+
+    /// Shortest paths from `start` where every step costs 1, only moving
+    /// into cells that are `passable` and within the bounds. The start
+    /// itself doesn't need to be passable.
+    fn bfs(&self, start: C, hood: Neighborhood, passable: impl Fn(C, &T) -> bool) -> Paths<C>
+    where
+        Self: Sized,
+    {
+        path::bfs(self, start, hood, passable)
     }
 
-    pub fn new(x: i32, y: i32) -> Self {
-        Coord2D(x, y)
+    /// Shortest paths from `start`, where `cost` says what it takes to step
+    /// into a cell (or `None` if you can't), only moving within the bounds
+    fn dijkstra(
+        &self,
+        start: C,
+        hood: Neighborhood,
+        cost: impl Fn(C, &T) -> Option<usize>,
+    ) -> Paths<C>
+    where
+        Self: Sized,
+    {
+        path::dijkstra(self, start, hood, cost)
     }
 
-    pub fn up(self, count: i32) -> Self {
-        Coord2D(self.0, self.1 + count)
+    /// Like `dijkstra`, but heads for `goal` and stops once it's there.
+    /// `heuristic` guesses the cost left from a cell; it must never guess
+    /// high or the path might not be the shortest. For steps costing at least
+    /// 1 the Manhattan (von Neumann) or Chebyshev (Moore) distance works.
+    fn astar(
+        &self,
+        start: C,
+        goal: C,
+        hood: Neighborhood,
+        cost: impl Fn(C, &T) -> Option<usize>,
+        heuristic: impl Fn(C) -> usize,
+    ) -> Option<(usize, Vec<C>)>
+    where
+        Self: Sized,
+    {
+        path::astar(self, start, goal, hood, cost, heuristic)
     }
-    pub fn down(self, count: i32) -> Self {
-        Coord2D(self.0, self.1 - count)
+
+    /// Every cell connected to `start` through cells where `inside` holds,
+    /// staying within the bounds. Empty if `start` itself isn't inside.
+    fn flood_fill(
+        &self,
+        start: C,
+        hood: Neighborhood,
+        inside: impl Fn(C, &T) -> bool,
+    ) -> BTreeSet<C>
+    where
+        Self: Sized,
+    {
+        region::flood_fill(self, start, hood, inside)
     }
-    pub fn left(self, count: i32) -> Self {
-        Coord2D(self.0 - count, self.1)
+
+    /// Splits the cells within the bounds where `inside` holds into
+    /// connected regions
+    fn components(&self, hood: Neighborhood, inside: impl Fn(C, &T) -> bool) -> Components<C>
+    where
+        Self: Sized,
+    {
+        region::components(self, hood, inside)
     }
-    pub fn right(self, count: i32) -> Self {
-        Coord2D(self.0 + count, self.1)
+
+    /// The `open` cells that can't be reached from outside the bounds by
+    /// moving through open cells, like air pockets inside a lava droplet
+    fn enclosed(&self, hood: Neighborhood, open: impl Fn(C, &T) -> bool) -> BTreeSet<C>
+    where
+        Self: Sized,
+    {
+        region::enclosed(self, hood, open)
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct ArbGrid<T> {
-    map: BTreeMap<Coord2D, T>,
+#[derive(Debug, Clone)]
+pub struct ArbGrid<T, C = Coord2D> {
+    map: BTreeMap<C, T>,
     default: T,
-    min: Option<Coord2D>,
-    max: Option<Coord2D>,
+    min: Option<C>,
+    max: Option<C>,
 }
 
-impl<T> ArbGrid<T>
+impl<T, C> ArbGrid<T, C>
 where
     T: Clone + Debug + PartialEq,
+    C: Coord,
 {
-    pub fn new(default: T) -> ArbGrid<T> {
+    pub fn new(default: T) -> ArbGrid<T, C> {
         ArbGrid {
             map: BTreeMap::new(),
             default,
@@ -52,16 +225,19 @@ where
         }
     }
 
-    pub fn get(&self, loc: Coord2D) -> &T {
+    pub fn get(&self, loc: C) -> &T {
         self.map.get(&loc).unwrap_or(&self.default)
     }
 
-    pub fn get_mut(&mut self, loc: Coord2D) -> &mut T {
-        let default = &self.default;
-        self.map.entry(loc).or_insert_with(|| default.clone())
+    pub fn get_mut(&mut self, loc: C) -> &mut T {
+        if !self.map.contains_key(&loc) {
+            // Go through insert so the bounds cover it
+            self.insert(loc, self.default.clone());
+        }
+        self.map.get_mut(&loc).unwrap()
     }
 
-    pub fn insert(&mut self, loc: Coord2D, value: T) -> Option<T> {
+    pub fn insert(&mut self, loc: C, value: T) -> Option<T> {
         if let Some(min) = self.min {
             self.min = Some(min.bound_min(loc));
         } else {
@@ -75,6 +251,117 @@ where
         self.map.insert(loc, value)
     }
 
+    /// Forgets every cell, leaving only the default
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.min = None;
+        self.max = None;
+    }
+
+    /// Takes `loc` back out, shrinking the bounds if it was on the edge
+    pub fn remove(&mut self, loc: C) -> Option<T> {
+        let old = self.map.remove(&loc)?;
+        if let Some((lo, hi)) = self.bounds() {
+            if (0..C::DIMS).any(|i| loc.axis(i) == lo.axis(i) || loc.axis(i) == hi.axis(i)) {
+                self.fit_bounds();
+            }
+        }
+        Some(old)
+    }
+
+    /// Keeps only the cells where `keep` is true
+    pub fn retain(&mut self, mut keep: impl FnMut(C, &T) -> bool) {
+        self.map.retain(|loc, v| keep(*loc, v));
+        self.fit_bounds();
+    }
+
+    /// Forgets every cell holding the default
+    pub fn trim(&mut self) {
+        let default = self.default.clone();
+        self.retain(|_, v| *v != default);
+    }
+
+    /// Shrinks the bounds back down to just what's stored
+    fn fit_bounds(&mut self) {
+        let mut keys = self.map.keys();
+        let first = keys.next().copied();
+        self.min = first;
+        self.max = first;
+        for loc in keys {
+            self.min = self.min.map(|m| m.bound_min(*loc));
+            self.max = self.max.map(|m| m.bound_max(*loc));
+        }
+    }
+
+    pub fn bounds(&self) -> Option<(C, C)> {
+        Some((self.min?, self.max?))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&C, &T)> + '_ {
+        self.map.iter()
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&C, &mut T)> + '_ {
+        self.map.iter_mut() // Safe because the keys aren't getting changed
+    }
+
+    /// The same cells at new coordinates, e.g. to lift a parsed 2D grid into 3D
+    pub fn map_coords<D: Coord>(&self, f: impl Fn(C) -> D) -> ArbGrid<T, D> {
+        let mut out = ArbGrid::new(self.default.clone());
+        for (loc, v) in self.map.iter() {
+            out.insert(f(*loc), v.clone());
+        }
+        out
+    }
+}
+
+/// Cells holding the default don't count, so two grids that would draw the
+/// same are equal, however they got that way
+impl<T: PartialEq, C: PartialEq> PartialEq for ArbGrid<T, C> {
+    fn eq(&self, other: &Self) -> bool {
+        fn set<T: PartialEq, C>(grid: &ArbGrid<T, C>) -> Vec<(&C, &T)> {
+            grid.map
+                .iter()
+                .filter(|(_, v)| **v != grid.default)
+                .collect()
+        }
+        self.default == other.default && set(self) == set(other)
+    }
+}
+
+impl<T: Eq, C: Eq> Eq for ArbGrid<T, C> {}
+
+impl<T, C> Grid<T, C> for ArbGrid<T, C>
+where
+    T: Clone + Debug + PartialEq,
+    C: Coord,
+{
+    fn default_value(&self) -> &T {
+        &self.default
+    }
+    fn get(&self, loc: C) -> &T {
+        ArbGrid::get(self, loc)
+    }
+    fn get_mut(&mut self, loc: C) -> &mut T {
+        ArbGrid::get_mut(self, loc)
+    }
+    fn insert(&mut self, loc: C, value: T) -> Option<T> {
+        ArbGrid::insert(self, loc, value)
+    }
+    fn bounds(&self) -> Option<(C, C)> {
+        ArbGrid::bounds(self)
+    }
+    fn cells<'a>(&'a self) -> impl Iterator<Item = (C, &'a T)>
+    where
+        T: 'a,
+    {
+        self.map.iter().map(|(loc, v)| (*loc, v))
+    }
+}
+
+impl<T> ArbGrid<T, Coord2D>
+where
+    T: Clone + Debug + PartialEq,
+{
     pub fn to_string(&self, convert_fn: &dyn Fn(&T, Coord2D) -> char) -> String {
         if self.min.is_none() || self.max.is_none() {
             return "".to_string();
@@ -104,21 +391,11 @@ where
         out
     }
 
-    pub fn trim(&mut self) {
-        let old_map = std::mem::take(&mut self.map);
-        self.min = None;
-        self.max = None;
-        let default = self.default.clone();
-        for (k, v) in old_map.into_iter().filter(|(_k, v)| *v != default) {
-            self.insert(k, v);
-        }
-    }
-
     pub fn from_str(
         origin: Coord2D,
         s: &str,
         convert_fn: &dyn Fn(char, Coord2D) -> Option<T>,
-    ) -> ArbGrid<T>
+    ) -> ArbGrid<T, Coord2D>
     where
         T: Default,
     {
@@ -141,7 +418,7 @@ where
         origin: Coord2D,
         s: &str,
         convert_fn: &dyn Fn(char, Coord2D) -> Option<T>,
-    ) -> ArbGrid<T>
+    ) -> ArbGrid<T, Coord2D>
     where
         T: Default,
     {
@@ -158,25 +435,49 @@ where
         }
         out
     }
+}
 
-    pub fn bounds(&self) -> Option<(Coord2D, Coord2D)> {
-        if self.min.is_none() || self.max.is_none() {
-            None
-        } else {
-            Some((self.min.unwrap(), self.max.unwrap()))
+impl<T> ArbGrid<T, Coord3D>
+where
+    T: Clone + Debug + PartialEq,
+{
+    /// The cells with the given z, as a 2D grid
+    pub fn slice_z(&self, z: i32) -> ArbGrid<T, Coord2D> {
+        let mut out = ArbGrid::new(self.default.clone());
+        for (loc, v) in self.map.iter().filter(|(loc, _)| loc.2 == z) {
+            out.insert(loc.flatten(), v.clone());
         }
+        out
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = (&Coord2D, &T)> + '_ {
-        self.map.iter()
-    }
-    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&Coord2D, &mut T)> + '_ {
-        self.map.iter_mut() // Safe because the keys aren't getting changed
+    /// Each z-layer in the bounds, top-down like `to_string_ogl`, under a
+    /// `z=N` header and over the full x/y extent so the layers line up
+    pub fn to_string_slices(&self, convert_fn: &dyn Fn(&T, Coord3D) -> char) -> String {
+        let (lo, hi) = match self.bounds() {
+            Some(b) => b,
+            None => return "".to_string(),
+        };
+        let mut layers = Vec::new();
+        for z in lo.2..=hi.2 {
+            let mut out = format!("z={}\n", z);
+            for y in lo.1..=hi.1 {
+                for x in lo.0..=hi.0 {
+                    let loc = Coord3D(x, y, z);
+                    out.push(convert_fn(self.get(loc), loc));
+                }
+                out.push('\n');
+            }
+            layers.push(out);
+        }
+        layers.join("\n")
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use std::time::Duration;
+
     #[test]
     fn it_works() {
         assert_eq!(2 + 2, 4);
@@ -184,4 +485,1198 @@ mod tests {
 
     #[test]
     fn round_trip() {}
+
+    #[test]
+    fn unbounded_neighbors() {
+        let grid = ArbGrid::from_str(Coord2D(0, 0), ".#.\n#@.\n...", &|c, _xy| Some(c));
+        let mut around: Vec<(Coord2D, char)> = grid
+            .neighbors(Coord2D(1, 1), Neighborhood::VonNeumann, Edges::Unbounded)
+            .map(|(xy, c)| (xy, *c))
+            .collect();
+        around.sort();
+        assert_eq!(
+            around,
+            vec!(
+                (Coord2D(0, 1), '#'),
+                (Coord2D(1, 0), '.'),
+                (Coord2D(1, 2), '#'),
+                (Coord2D(2, 1), '.')
+            )
+        );
+        // Off the edge gives the default
+        assert!(grid
+            .neighbors(Coord2D(0, 0), Neighborhood::VonNeumann, Edges::Unbounded)
+            .any(|(_, c)| *c == char::default()));
+    }
+
+    #[test]
+    fn neighbor_counts() {
+        assert_eq!(Coord2D(0, 0).surrounding().len(), 8);
+        assert_eq!(Coord3D(0, 0, 0).orthogonal().len(), 6);
+        assert_eq!(Coord3D(0, 0, 0).surrounding().len(), 26);
+        assert_eq!(Coord4D(0, 0, 0, 0).orthogonal().len(), 8);
+        assert_eq!(Coord4D(0, 0, 0, 0).surrounding().len(), 80);
+        assert!(!Coord3D(1, 1, 1).surrounding().contains(&Coord3D(1, 1, 1)));
+        assert_eq!(
+            Coord3D::box_iter(Coord3D(0, 0, 0), Coord3D(1, 2, 3)).len(),
+            24
+        );
+    }
+
+    fn conway_cubes<C: Coord>(mut grid: ArbGrid<bool, C>, cycles: usize) -> usize {
+        for _ in 0..cycles {
+            let (lo, hi) = grid.bounds().unwrap();
+            let lo = lo.offset(C::from_axes(|_| -1));
+            let hi = hi.offset(C::from_axes(|_| 1));
+            let mut next = ArbGrid::new(false);
+            for loc in C::box_iter(lo, hi) {
+                let n = grid
+                    .neighbors(loc, Neighborhood::Moore, Edges::Unbounded)
+                    .filter(|(_, on)| **on)
+                    .count();
+                if n == 3 || (n == 2 && *grid.get(loc)) {
+                    next.insert(loc, true);
+                }
+            }
+            grid = next;
+        }
+        grid.iter().count()
+    }
+
+    #[test]
+    fn conway_cubes_eg() {
+        let flat = ArbGrid::from_str_ogl(Coord2D(0, 0), ".#.\n..#\n###", &|c, _xy| {
+            if c == '#' {
+                Some(true)
+            } else {
+                None
+            }
+        });
+        let cube: ArbGrid<bool, Coord3D> = flat.map_coords(Coord3D::from);
+        assert_eq!(cube.bounds(), Some((Coord3D(0, 0, 0), Coord3D(2, 2, 0))));
+        assert_eq!(conway_cubes(cube, 6), 112);
+        assert_eq!(conway_cubes(flat.map_coords(Coord4D::from), 6), 848);
+    }
+
+    #[test]
+    fn droplet_surface() {
+        let mut droplet = ArbGrid::new(false);
+        for (x, y, z) in [(1, 1, 1), (2, 1, 1)].iter() {
+            droplet.insert(Coord3D(*x, *y, *z), true);
+        }
+        let exposed = droplet
+            .iter()
+            .map(|(loc, _)| {
+                droplet
+                    .neighbors(*loc, Neighborhood::VonNeumann, Edges::Unbounded)
+                    .filter(|(_, lava)| !**lava)
+                    .count()
+            })
+            .sum::<usize>();
+        assert_eq!(exposed, 10);
+        assert_eq!(droplet.bounds(), Some((Coord3D(1, 1, 1), Coord3D(2, 1, 1))));
+    }
+
+    #[test]
+    fn slices() {
+        let mut grid = ArbGrid::new('.');
+        grid.insert(Coord3D(0, 0, -1), '#');
+        grid.insert(Coord3D(1, 1, 0), '#');
+        assert_eq!(
+            grid.to_string_slices(&|c, _xyz| *c),
+            "z=-1\n#.\n..\n\nz=0\n..\n.#\n"
+        );
+        assert_eq!(
+            grid.slice_z(0).bounds(),
+            Some((Coord2D(1, 1), Coord2D(1, 1)))
+        );
+    }
+
+    #[test]
+    fn neighbor_edges() {
+        let grid = ArbGrid::from_str_ogl(Coord2D(0, 0), "abc\ndef\nghi", &|c, _xy| Some(c));
+        let vals = |hood, edges| {
+            let mut v: Vec<char> = grid
+                .neighbors(Coord2D(0, 0), hood, edges)
+                .map(|(_, c)| *c)
+                .collect();
+            v.sort_unstable();
+            v.into_iter().collect::<String>()
+        };
+        assert_eq!(vals(Neighborhood::VonNeumann, Edges::Clip), "bd");
+        assert_eq!(vals(Neighborhood::Moore, Edges::Clip), "bde");
+        assert_eq!(vals(Neighborhood::VonNeumann, Edges::Wrap), "bcdg");
+        assert_eq!(vals(Neighborhood::Moore, Edges::Wrap), "bcdefghi");
+        assert_eq!(vals(Neighborhood::Moore, Edges::Unbounded), "\0\0\0\0\0bde");
+    }
+
+    fn seat_grid(s: &str) -> ArbGrid<char> {
+        ArbGrid::from_str_ogl(Coord2D(0, 0), s, &|c, _xy| Some(c))
+    }
+
+    fn seen_occupied(grid: &ArbGrid<char>, loc: Coord2D) -> usize {
+        grid.line_of_sight(loc, Neighborhood::Moore, |_, c| *c != '.')
+            .filter(|(_, c)| **c == '#')
+            .count()
+    }
+
+    #[test]
+    fn line_of_sight() {
+        let grid = seat_grid(
+            ".......#.\n...#.....\n.#.......\n.........\n..#L....#\n....#....\n.........\n#........\n...#.....",
+        );
+        assert_eq!(seen_occupied(&grid, Coord2D(3, 4)), 8);
+
+        let grid = seat_grid(".............\n.L.L.#.#.#.#.\n.............");
+        assert_eq!(seen_occupied(&grid, Coord2D(1, 1)), 0);
+        let seen: Vec<Coord2D> = grid
+            .line_of_sight(Coord2D(1, 1), Neighborhood::Moore, |_, c| *c != '.')
+            .map(|(xy, _)| xy)
+            .collect();
+        assert_eq!(seen, vec!(Coord2D(3, 1)));
+    }
+
+    #[test]
+    fn seating_by_sight() {
+        let mut grid = seat_grid(
+            "L.LL.LL.LL\nLLLLLLL.LL\nL.L.L..L..\nLLLL.LL.LL\nL.LL.LL.LL\nL.LLLLL.LL\n..L.L.....\nLLLLLLLLLL\nL.LLLLLL.L\nL.LLLLL.LL",
+        );
+        loop {
+            let mut next = grid.clone();
+            for (xy, seat) in next.iter_mut() {
+                let seen = seen_occupied(&grid, *xy);
+                match *seat {
+                    'L' if seen == 0 => *seat = '#',
+                    '#' if seen >= 5 => *seat = 'L',
+                    _ => {}
+                }
+            }
+            if next == grid {
+                break;
+            }
+            grid = next;
+        }
+        assert_eq!(grid.iter().filter(|(_, c)| **c == '#').count(), 26);
+    }
+
+    const RISK: &str = "1163751742\n1381373672\n2136511328\n3694931569\n7463417111\n1319128137\n1359912421\n3125421639\n1293138521\n2311944581";
+
+    #[test]
+    fn weighted_paths() {
+        // 2021 day 15: the cost is the digit of the cell you step into
+        let risk = ArbGrid::from_str_ogl(Coord2D(0, 0), RISK, &|c, _xy| c.to_digit(10));
+        let cost = |_xy, r: &u32| Some(*r as usize);
+        let goal = Coord2D(9, 9);
+
+        let paths = risk.dijkstra(Coord2D(0, 0), Neighborhood::VonNeumann, cost);
+        assert_eq!(paths.distance(goal), Some(40));
+        let path = paths.path_to(goal).unwrap();
+        assert_eq!(path.first(), Some(&Coord2D(0, 0)));
+        assert_eq!(path.last(), Some(&goal));
+        assert_eq!(
+            path.iter().skip(1).map(|xy| *risk.get(*xy)).sum::<u32>(),
+            40
+        );
+        assert_eq!(paths.distance_map().get(Coord2D(1, 0)), &Some(1));
+
+        let (dist, path) = risk
+            .astar(Coord2D(0, 0), goal, Neighborhood::VonNeumann, cost, |xy| {
+                xy.manhattan(goal) as usize
+            })
+            .unwrap();
+        assert_eq!(dist, 40);
+        assert_eq!(path.len(), 19);
+        assert_eq!(
+            path.iter().skip(1).map(|xy| *risk.get(*xy)).sum::<u32>(),
+            40
+        );
+    }
+
+    #[test]
+    fn unweighted_paths() {
+        let maze = ArbGrid::from_str_ogl(
+            Coord2D(0, 0),
+            "S.#.....\n#.#.###.\n#...#...\n####.#.#\n.....#.E",
+            &|c, _xy| Some(c),
+        );
+        let paths = maze.bfs(Coord2D(0, 0), Neighborhood::VonNeumann, |_, c| *c != '#');
+        assert_eq!(paths.distance(Coord2D(7, 4)), Some(17));
+        assert_eq!(paths.path_to(Coord2D(7, 4)).unwrap().len(), 18);
+        // Walled off, unless you can squeeze through diagonally
+        assert_eq!(paths.distance(Coord2D(0, 4)), None);
+        let diag = maze.bfs(Coord2D(0, 0), Neighborhood::Moore, |_, c| *c != '#');
+        assert_eq!(diag.distance(Coord2D(7, 4)), Some(7));
+        assert_eq!(diag.distance(Coord2D(0, 4)), Some(8));
+
+        let walled = ArbGrid::from_str_ogl(Coord2D(0, 0), "S#.", &|c, _xy| Some(c));
+        let paths = walled.bfs(Coord2D(0, 0), Neighborhood::Moore, |_, c| *c != '#');
+        assert_eq!(paths.distance(Coord2D(2, 0)), None);
+        assert_eq!(paths.path_to(Coord2D(2, 0)), None);
+        assert_eq!(paths.distance_map().iter().count(), 1);
+    }
+
+    #[test]
+    fn generic_search() {
+        // Smallest number of +1/*2 steps from 1 to 10
+        let res = shortest_path(1u32, |n| vec![(n + 1, 1), (n * 2, 1)], |n| *n == 10);
+        assert_eq!(res, Some((4, vec!(1, 2, 4, 5, 10))));
+        assert_eq!(shortest_path(1u32, |_| vec![], |n| *n == 2), None);
+    }
+
+    fn life(cell: &Cell<bool>) -> bool {
+        let n = cell.count(|b| *b);
+        n == 3 || (n == 2 && *cell.value)
+    }
+
+    fn life_grid(s: &str) -> ArbGrid<bool> {
+        ArbGrid::from_str_ogl(Coord2D(0, 0), s, &|c, _xy| Some(c == '#'))
+    }
+
+    #[test]
+    fn automaton_cycles() {
+        let blinker = life_grid("...\n###\n...");
+        let mut auto = Automaton::sparse(blinker.clone(), Neighborhood::Moore);
+        assert_eq!(
+            auto.find_cycle(life, 10),
+            Some(Cycle {
+                start: 0,
+                period: 2
+            })
+        );
+        assert_eq!(auto.generation(), 2);
+        // Two steps is just enough to see it come back
+        let mut auto = Automaton::sparse(blinker.clone(), Neighborhood::Moore);
+        assert_eq!(auto.find_cycle(life, 1), None);
+        assert_eq!(auto.generation(), 1);
+        let mut auto = Automaton::sparse(blinker.clone(), Neighborhood::Moore);
+        assert!(auto.find_cycle(life, 2).is_some());
+        // Keyed on cell count alone, every blinker layout looks the same
+        let mut auto = Automaton::sparse(blinker.clone(), Neighborhood::Moore);
+        assert_eq!(
+            auto.find_cycle_by(life, |g| g.iter().count(), 10),
+            Some(Cycle {
+                start: 0,
+                period: 1
+            })
+        );
+
+        let mut still = Automaton::sparse(life_grid("##\n##"), Neighborhood::Moore);
+        assert_eq!(still.run_until_stable(life, 10), Some(0));
+
+        // A glider on a 6x6 torus comes back where it started after 24 steps
+        let glider = life_grid(".#....\n..#...\n###...\n......\n......\n......");
+        let bounds = (Coord2D(0, 0), Coord2D(5, 5));
+        let mut auto = Automaton::toroidal(glider.clone(), bounds, Neighborhood::Moore);
+        assert_eq!(
+            auto.find_cycle(life, 100),
+            Some(Cycle {
+                start: 0,
+                period: 24
+            })
+        );
+        // ...but without the wrap it hits the corner and turns into a block
+        let mut auto = Automaton::bounded(glider, bounds, Neighborhood::Moore);
+        assert_eq!(auto.run_until_stable(life, 100), Some(15));
+        assert_eq!(auto.grid().iter().count(), 4);
+        assert_eq!(auto.run_until_stable(life, 0), None);
+    }
+
+    #[test]
+    fn automaton_sparse_growth() {
+        let glider = life_grid(".#.\n..#\n###");
+        let mut auto = Automaton::sparse(glider, Neighborhood::Moore);
+        for _ in 0..8 {
+            auto.step(life);
+        }
+        // Still a glider, two cells further along each way
+        assert_eq!(auto.grid().iter().count(), 5);
+        assert_eq!(auto.grid().bounds(), Some((Coord2D(2, 2), Coord2D(4, 4))));
+    }
+
+    #[test]
+    fn automaton_seating() {
+        // 2020 day 11, with the part two rule looking past the floor
+        let seats = ArbGrid::from_str_ogl(
+            Coord2D(0, 0),
+            "L.LL.LL.LL\nLLLLLLL.LL\nL.L.L..L..\nLLLL.LL.LL\nL.LL.LL.LL\nL.LLLLL.LL\n..L.L.....\nLLLLLLLLLL\nL.LLLLLL.L\nL.LLLLL.LL",
+            &|c, _xy| Some(c),
+        );
+        let bounds = seats.bounds().unwrap();
+        let occupied = |g: &ArbGrid<char>| g.iter().filter(|(_, c)| **c == '#').count();
+
+        let mut near = Automaton::bounded(seats.clone(), bounds, Neighborhood::Moore);
+        near.run_until_stable(
+            |cell| match (*cell.value, cell.count(|c| *c == '#')) {
+                ('L', 0) => '#',
+                ('#', n) if n >= 4 => 'L',
+                (c, _) => c,
+            },
+            100,
+        );
+        assert_eq!(occupied(near.grid()), 37);
+
+        let mut far = Automaton::bounded(seats, bounds, Neighborhood::Moore);
+        let stable = far.run_until_stable(
+            |cell| {
+                let seen = cell
+                    .grid()
+                    .line_of_sight(cell.loc, Neighborhood::Moore, |_, c| *c != '.')
+                    .filter(|(_, c)| **c == '#')
+                    .count();
+                match (*cell.value, seen) {
+                    ('L', 0) => '#',
+                    ('#', n) if n >= 5 => 'L',
+                    (c, _) => c,
+                }
+            },
+            100,
+        );
+        assert_eq!(stable, Some(6));
+        assert_eq!(occupied(far.grid()), 26);
+    }
+
+    fn checker() -> ArbGrid<bool> {
+        // Lit in the top left when drawn y-up
+        ArbGrid::from_str(Coord2D(0, 0), "#.\n..", &|c, _xy| Some(c == '#'))
+    }
+
+    fn bw(lit: &bool, _xy: Coord2D) -> Rgb {
+        if *lit {
+            [255, 255, 255]
+        } else {
+            [0, 0, 0]
+        }
+    }
+
+    #[test]
+    fn ppm() {
+        let mut out = Vec::new();
+        checker()
+            .write_ppm(&mut out, ImageOptions::default(), &bw)
+            .unwrap();
+        let mut expected = b"P6\n2 2\n255\n".to_vec();
+        expected.extend_from_slice(&[255, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(out, expected);
+
+        let mut out = Vec::new();
+        let opts = ImageOptions::new(2, Orientation::YDown);
+        checker().write_ppm(&mut out, opts, &bw).unwrap();
+        let header = b"P6\n4 4\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        let pixels: Vec<bool> = out[header.len()..].chunks(3).map(|p| p[0] == 255).collect();
+        assert_eq!(pixels.len(), 16);
+        // Now the lit cell is the bottom left 2x2
+        let lit: Vec<usize> = (0..16).filter(|i| pixels[*i]).collect();
+        assert_eq!(lit, vec!(8, 9, 12, 13));
+    }
+
+    #[test]
+    fn svg() {
+        let mut out = Vec::new();
+        checker()
+            .write_svg(&mut out, ImageOptions::new(10, Orientation::YUp), &bw)
+            .unwrap();
+        let svg = String::from_utf8(out).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r#"width="20" height="20" viewBox="0 0 2 2""#));
+        assert!(svg.contains(r##"<rect x="0" y="0" width="1" height="1" fill="#ffffff"/>"##));
+        // The bottom row is one run
+        assert!(svg.contains(r##"<rect x="0" y="1" width="2" height="1" fill="#000000"/>"##));
+        assert_eq!(svg.matches("<rect").count(), 3);
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn png() {
+        let mut out = Vec::new();
+        checker()
+            .write_png(&mut out, ImageOptions::new(3, Orientation::YUp), &bw)
+            .unwrap();
+        let decoder = png::Decoder::new(&out[..]);
+        let mut reader = decoder.read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!((info.width, info.height), (6, 6));
+        assert_eq!(&buf[..3], &[255, 255, 255]);
+        assert_eq!(&buf[6 * 3 * 5..6 * 3 * 5 + 3], &[0, 0, 0]);
+    }
+
+    #[test]
+    fn save_unknown() {
+        let err = checker()
+            .save_image("grid.bmp", ImageOptions::default(), &bw)
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    fn dot_style(lit: &bool, _xy: Coord2D) -> Style {
+        if *lit {
+            Style::plain('#').fg([255, 0, 0])
+        } else {
+            Style::plain('.')
+        }
+    }
+
+    #[test]
+    fn renderer_diffs() {
+        let mut grid = ArbGrid::from_str_ogl(Coord2D(0, 0), "#..\n...", &|c, _xy| Some(c == '#'));
+        let mut screen = Renderer::new(Vec::new()).orientation(Orientation::YDown);
+        screen.draw(&grid, &dot_style).unwrap();
+        grid.insert(Coord2D(2, 1), true);
+        screen.draw(&grid, &dot_style).unwrap();
+        let out = String::from_utf8(screen.finish().unwrap()).unwrap();
+
+        let (first, second) = out.split_at(out.rfind("\x1b[2;3H").unwrap());
+        assert_eq!(
+            first,
+            "\x1b[H\x1b[2J\x1b[1;1H\x1b[38;2;255;0;0m#\x1b[0m..\x1b[2;1H..."
+        );
+        // Only the changed cell gets redrawn
+        assert_eq!(second, "\x1b[2;3H\x1b[38;2;255;0;0m#\x1b[0m\x1b[3;1H");
+
+        // Growing moves everything, so it all gets drawn again
+        let mut screen = Renderer::new(Vec::new());
+        screen.draw(&grid, &dot_style).unwrap();
+        grid.insert(Coord2D(3, 0), false);
+        screen.draw(&grid, &dot_style).unwrap();
+        let out = String::from_utf8(screen.finish().unwrap()).unwrap();
+        assert_eq!(out.matches("\x1b[2J").count(), 2);
+    }
+
+    #[test]
+    fn asciicast() {
+        let mut grid = ArbGrid::new(false);
+        grid.insert(Coord2D(0, 0), true);
+        let cast = Asciicast::new(Vec::new(), 1, 1, Duration::from_millis(250)).unwrap();
+        let mut screen = Renderer::new(cast).max_fps(1000);
+        for _ in 0..3 {
+            screen.draw(&grid, &|_, _| Style::plain('"')).unwrap();
+            *grid.get_mut(Coord2D(0, 0)) ^= true;
+        }
+        let cast = screen.finish().unwrap().into_inner().unwrap();
+        let lines: Vec<String> = String::from_utf8(cast)
+            .unwrap()
+            .lines()
+            .map(|l| l.to_string())
+            .collect();
+        assert_eq!(
+            lines,
+            vec!(
+                r#"{"version": 2, "width": 1, "height": 1}"#,
+                r#"[0.000000, "o", "\u001b[H\u001b[2J\u001b[1;1H\""]"#,
+                // Nothing visible changed for two frames, but they still count
+                r#"[0.750000, "o", "\u001b[2;1H"]"#,
+            )
+        );
+    }
+
+    #[test]
+    fn get_mut_bounds() {
+        let mut grid = ArbGrid::new(0);
+        *grid.get_mut(Coord2D(-1, 2)) = 1;
+        *grid.get_mut(Coord2D(3, 0)) += 1;
+        assert_eq!(grid.bounds(), Some((Coord2D(-1, 0), Coord2D(3, 2))));
+    }
+
+    fn show(grid: &ArbGrid<char>) -> String {
+        grid.to_string(&|c, _xy| *c)
+    }
+
+    #[test]
+    fn rotations() {
+        let grid = ArbGrid::from_str(Coord2D(5, -3), "ab\ncd\nef", &|c, _xy| Some(c));
+        assert_eq!(show(&grid.rotate_cw()), "eca\nfdb\n");
+        assert_eq!(show(&grid.rotate_ccw()), "bdf\nace\n");
+        assert_eq!(show(&grid.rotate_180()), "fe\ndc\nba\n");
+        assert_eq!(show(&grid.flip_horizontal()), "ba\ndc\nfe\n");
+        assert_eq!(show(&grid.flip_vertical()), "ef\ncd\nab\n");
+        // Swapping x and y flips across the bottom-left to top-right diagonal
+        assert_eq!(show(&grid.transpose()), "fdb\neca\n");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+        assert_eq!(grid.rotate_cw().rotate_cw(), grid.rotate_180());
+        assert_eq!(
+            grid.rotate_cw().bounds(),
+            Some((Coord2D(5, -3), Coord2D(7, -2)))
+        );
+
+        let all = grid.orientations();
+        assert_eq!(all.len(), 8);
+        let mut drawn: Vec<String> = all.iter().map(show).collect();
+        drawn.sort();
+        drawn.dedup();
+        assert_eq!(drawn.len(), 8);
+        assert!(all.contains(&grid.transpose()));
+        assert!(all.contains(&grid.flip_vertical()));
+    }
+
+    #[test]
+    fn crop_and_paste() {
+        let grid = ArbGrid::from_str_ogl(Coord2D(0, 0), "abc\ndef\nghi", &|c, _xy| Some(c));
+        let corner = grid.crop(Coord2D(1, 1), Coord2D(5, 5));
+        assert_eq!(corner.to_string_ogl(&|c, _xy| *c), "ef\nhi\n");
+
+        let mut canvas = ArbGrid::new('.');
+        canvas.paste(&corner, Coord2D(-1, -1));
+        canvas.paste(&corner, Coord2D(2, 0));
+        assert_eq!(canvas.to_string_ogl(&|c, _xy| *c), "ef...\nhi.ef\n...hi\n");
+    }
+
+    #[test]
+    fn fold_paper() {
+        // 2021 day 13: fold the bottom half up along y=7, then the right
+        // half left along x=5
+        let dots = [
+            (6, 10),
+            (0, 14),
+            (9, 10),
+            (0, 3),
+            (10, 4),
+            (4, 11),
+            (6, 0),
+            (6, 12),
+            (4, 1),
+            (0, 13),
+            (10, 12),
+            (3, 4),
+            (3, 0),
+            (8, 4),
+            (1, 10),
+            (2, 14),
+            (8, 10),
+            (9, 0),
+        ];
+        let mut paper = ArbGrid::new(false);
+        for (x, y) in dots.iter() {
+            paper.insert(Coord2D(*x, *y), true);
+        }
+        let (lo, hi) = paper.bounds().unwrap();
+        let or = |a: &bool, b: &bool| *a || *b;
+
+        // Cropping only keeps what's there, so the far side might not reach
+        // all the way to the edge; mirror it across the fold line instead
+        let mut top = paper.crop(lo, Coord2D(hi.0, 6));
+        let bottom = paper.crop(Coord2D(lo.0, 8), hi);
+        top.paste_with(
+            &bottom.map_coords(|c| Coord2D(c.0, 14 - c.1)),
+            Coord2D(0, 0),
+            or,
+        );
+        assert_eq!(top.iter().filter(|(_, dot)| **dot).count(), 17);
+
+        // This time the right half is full width, so a flip lines up
+        let (lo, hi) = top.bounds().unwrap();
+        let mut left = top.crop(lo, Coord2D(4, hi.1));
+        let right = top.crop(Coord2D(6, lo.1), hi);
+        assert_eq!(right.bounds().unwrap().1 .0, 10);
+        left.paste_with(&right.flip_horizontal(), Coord2D(-6, 0), or);
+        assert_eq!(
+            left.to_string_ogl(&|dot, _xy| if *dot { '#' } else { '.' }),
+            "#####\n#...#\n#...#\n#...#\n#####\n"
+        );
+    }
+
+    #[test]
+    fn coord_math() {
+        let a = Coord2D(3, -2);
+        let b = Coord2D(-1, 5);
+        assert_eq!(a + b, Coord2D(2, 3));
+        assert_eq!(a - b, Coord2D(4, -7));
+        assert_eq!(a * 3, Coord2D(9, -6));
+        assert_eq!(-a, Coord2D(-3, 2));
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+        assert_eq!(a.manhattan(b), 11);
+        assert_eq!(Coord3D(1, 2, 3) + Coord3D(1, 1, 1), Coord3D(2, 3, 4));
+
+        // Ship waypoint from 2020 day 12: R90 turns (10, 4) into (4, -10)
+        assert_eq!(Coord2D(10, 4).rotate_cw(), Coord2D(4, -10));
+        assert_eq!(Coord2D(10, 4).rotate_ccw(), Coord2D(-4, 10));
+        assert_eq!(a.rotate_cw().rotate_ccw(), a);
+    }
+
+    #[test]
+    fn directions() {
+        for (s, dir) in [
+            ("U", Direction::Up),
+            ("n", Direction::Up),
+            ("^", Direction::Up),
+            ("v", Direction::Down),
+            ("S", Direction::Down),
+            ("<", Direction::Left),
+            ("W", Direction::Left),
+            ("E", Direction::Right),
+            ("NE", Direction::UpRight),
+            ("rd", Direction::DownRight),
+            ("v<", Direction::DownLeft),
+            ("NW", Direction::UpLeft),
+        ]
+        .iter()
+        {
+            assert_eq!(s.parse::<Direction>(), Ok(*dir), "{}", s);
+        }
+        assert!("NS".parse::<Direction>().is_err());
+        assert!("X".parse::<Direction>().is_err());
+        assert!("".parse::<Direction>().is_err());
+
+        assert_eq!(Direction::Up.turn_cw(), Direction::Right);
+        assert_eq!(Direction::Up.turn_ccw(), Direction::Left);
+        assert_eq!(Direction::UpLeft.turn_cw(), Direction::UpRight);
+        assert_eq!(Direction::Right.turn(270), Direction::Up);
+        assert_eq!(Direction::Right.turn(-45), Direction::UpRight);
+        assert_eq!(Direction::Left.reverse(), Direction::Right);
+        assert!(Direction::DownLeft.is_diagonal());
+
+        // The directions line up with the neighborhoods
+        let mut around: Vec<Coord2D> = Direction::ALL.iter().map(|d| d.offset()).collect();
+        let mut moore = Neighborhood::Moore.directions::<Coord2D>();
+        around.sort();
+        moore.sort();
+        assert_eq!(around, moore);
+        assert_eq!(Coord2D(1, 1).step(Direction::Down, 3), Coord2D(1, -2));
+        assert_eq!(Direction::Up.offset_ogl(), Coord2D(0, -1));
+    }
+
+    #[test]
+    fn dense_backing() {
+        let mut dense: DenseGrid<char> = DenseGrid::new('.');
+        let mut sparse: ArbGrid<char> = ArbGrid::new('.');
+        assert_eq!(dense.bounds(), None);
+        assert_eq!(dense.iter().count(), 0);
+
+        // Wander outward in every direction so it has to grow a few times
+        for (i, loc) in [(0, 0), (3, 1), (-5, 2), (2, -7), (-1, -1), (10, 10)]
+            .iter()
+            .enumerate()
+        {
+            let c = (b'a' + i as u8) as char;
+            assert_eq!(dense.insert(Coord2D(loc.0, loc.1), c), None);
+            sparse.insert(Coord2D(loc.0, loc.1), c);
+        }
+        assert_eq!(dense.insert(Coord2D(3, 1), 'B'), Some('b'));
+        sparse.insert(Coord2D(3, 1), 'B');
+        *dense.get_mut(Coord2D(4, 4)) = 'z';
+        *sparse.get_mut(Coord2D(4, 4)) = 'z';
+
+        assert_eq!(dense.get(Coord2D(-5, 2)), &'c');
+        assert_eq!(dense.get(Coord2D(1, 1)), &'.');
+        assert_eq!(dense.get(Coord2D(100, 0)), &'.');
+        assert_eq!(dense.bounds(), Some((Coord2D(-5, -7), Coord2D(10, 10))));
+        // Same cells in the same order, and default-valued gaps stay unset
+        let from_sparse: Vec<(Coord2D, char)> = sparse.iter().map(|(c, v)| (*c, *v)).collect();
+        let from_dense: Vec<(Coord2D, char)> = dense.iter().map(|(c, v)| (c, *v)).collect();
+        assert_eq!(from_dense, from_sparse);
+        assert_eq!(ArbGrid::from(&dense), sparse);
+        assert_eq!(DenseGrid::from(&sparse), dense);
+
+        // Equality doesn't care how much room was set aside
+        let mut roomy = DenseGrid::with_bounds('.', Coord2D(-50, -50), Coord2D(50, 50));
+        for (loc, v) in sparse.iter() {
+            roomy.insert(*loc, *v);
+        }
+        assert_eq!(roomy, dense);
+
+        dense.clear();
+        assert_eq!(dense.bounds(), None);
+        assert_eq!(dense.get(Coord2D(0, 0)), &'.');
+
+        // Works past two dimensions too
+        let mut cube: DenseGrid<u8, Coord3D> = DenseGrid::new(0);
+        cube.insert(Coord3D(1, 2, 3), 1);
+        cube.insert(Coord3D(-1, 0, 5), 2);
+        assert_eq!(cube.get(Coord3D(1, 2, 3)), &1);
+        assert_eq!(cube.get(Coord3D(-1, 0, 5)), &2);
+        assert_eq!(cube.iter().count(), 2);
+    }
+
+    /// Anything written against `Grid` gives the same answers on either backing
+    fn open_cells_seen<G: Grid<char>>(grid: &G) -> (usize, Option<usize>, Option<usize>) {
+        let seen = grid
+            .line_of_sight(Coord2D(2, 2), Neighborhood::Moore, |_, c| *c == '#')
+            .count();
+        let paths = grid.bfs(Coord2D(0, 0), Neighborhood::VonNeumann, |_, c| *c != '#');
+        (
+            seen,
+            paths.distance(Coord2D(4, 0)),
+            paths.distance(Coord2D(4, 4)),
+        )
+    }
+
+    #[test]
+    fn generic_backings() {
+        let sparse = ArbGrid::from_str_ogl(
+            Coord2D(0, 0),
+            "#.#..\n.....\n#.@.#\n.#.#.\n..#..",
+            &|c, _xy| Some(c),
+        );
+        let dense = DenseGrid::from(&sparse);
+        // The bottom-right corner is walled off
+        assert_eq!(open_cells_seen(&sparse), (7, Some(6), None));
+        assert_eq!(open_cells_seen(&dense), open_cells_seen(&sparse));
+        assert_eq!(
+            dense
+                .neighbors(Coord2D(0, 0), Neighborhood::Moore, Edges::Wrap)
+                .count(),
+            8
+        );
+    }
+
+    #[test]
+    fn remove_and_retain() {
+        let mut grid = ArbGrid::from_str_ogl(Coord2D(0, 0), "a..\n.b.\n..c", &|c, _xy| match c {
+            '.' => None,
+            c => Some(c),
+        });
+        assert_eq!(grid.bounds(), Some((Coord2D(0, 0), Coord2D(2, 2))));
+        assert_eq!(grid.remove(Coord2D(1, 1)), Some('b'));
+        assert_eq!(grid.remove(Coord2D(1, 1)), None);
+        assert_eq!(grid.bounds(), Some((Coord2D(0, 0), Coord2D(2, 2))));
+        assert_eq!(grid.remove(Coord2D(2, 2)), Some('c'));
+        assert_eq!(grid.bounds(), Some((Coord2D(0, 0), Coord2D(0, 0))));
+
+        // Touching a cell through get_mut stretches the bounds, and taking it
+        // back out shrinks them again
+        grid.get_mut(Coord2D(5, -3));
+        assert_eq!(grid.bounds(), Some((Coord2D(0, -3), Coord2D(5, 0))));
+        grid.remove(Coord2D(5, -3));
+        assert_eq!(grid.bounds(), Some((Coord2D(0, 0), Coord2D(0, 0))));
+
+        for x in 0..5 {
+            grid.insert(Coord2D(x, 1), char::from(b'0' + x as u8));
+        }
+        grid.retain(|xy, _| xy.0 % 2 == 1);
+        assert_eq!(grid.iter().count(), 2);
+        assert_eq!(grid.bounds(), Some((Coord2D(1, 1), Coord2D(3, 1))));
+        grid.retain(|_, _| false);
+        assert_eq!(grid.bounds(), None);
+
+        // Same on the dense side
+        let mut dense: DenseGrid<char> = DenseGrid::new('.');
+        dense.insert(Coord2D(-2, 0), 'x');
+        dense.insert(Coord2D(4, 4), 'y');
+        assert_eq!(dense.remove(Coord2D(4, 4)), Some('y'));
+        assert_eq!(dense.remove(Coord2D(40, 4)), None);
+        assert_eq!(dense.bounds(), Some((Coord2D(-2, 0), Coord2D(-2, 0))));
+        dense.retain(|_, v| *v != 'x');
+        assert_eq!(dense.bounds(), None);
+    }
+
+    #[test]
+    fn semantic_equality() {
+        let mut a: ArbGrid<u8> = ArbGrid::new(0);
+        let mut b: ArbGrid<u8> = ArbGrid::new(0);
+        a.insert(Coord2D(1, 1), 7);
+        b.insert(Coord2D(1, 1), 7);
+        b.insert(Coord2D(9, 9), 0);
+        *b.get_mut(Coord2D(-4, 2)) += 0;
+        assert_eq!(a, b);
+        assert_ne!(a.bounds(), b.bounds());
+
+        *b.get_mut(Coord2D(9, 9)) = 1;
+        assert_ne!(a, b);
+        // A different default is a different grid, even with the same cells
+        assert_ne!(a, ArbGrid::new(1));
+
+        let mut dense = DenseGrid::from(&a);
+        dense.insert(Coord2D(-20, 0), 0);
+        assert_eq!(dense, DenseGrid::from(&a));
+    }
+
+    #[test]
+    fn regions() {
+        let grid = ArbGrid::from_str_ogl(
+            Coord2D(0, 0),
+            "##..#\n#...#\n..#..\n.#.##\n##.#.",
+            &|c, _xy| Some(c == '#'),
+        );
+        let parts = grid.components(Neighborhood::VonNeumann, |_, wall| *wall);
+        assert_eq!(parts.sizes(), vec![3, 3, 3, 2, 1]);
+        let corner = parts.label(Coord2D(0, 0)).unwrap();
+        assert_eq!(
+            parts.regions()[corner],
+            Region {
+                size: 3,
+                bounds: (Coord2D(0, 0), Coord2D(1, 1))
+            }
+        );
+        assert_eq!(parts.cells(corner).count(), 3);
+        assert_eq!(parts.label(Coord2D(2, 0)), None);
+        // Diagonals join the wall in the middle up with its neighbors
+        let moore = grid.components(Neighborhood::Moore, |_, wall| *wall);
+        assert_eq!(moore.sizes(), vec![7, 3, 2]);
+
+        let open = grid.flood_fill(Coord2D(2, 0), Neighborhood::VonNeumann, |_, wall| !*wall);
+        assert_eq!(open.len(), 10);
+        assert!(open.contains(&Coord2D(0, 3)));
+        assert!(grid
+            .flood_fill(Coord2D(0, 0), Neighborhood::VonNeumann, |_, wall| !*wall)
+            .is_empty());
+
+        // Too big to recurse over
+        let mut field: DenseGrid<bool> =
+            DenseGrid::with_bounds(false, Coord2D(0, 0), Coord2D(299, 299));
+        field.insert(Coord2D(299, 299), false);
+        field.insert(Coord2D(0, 0), false);
+        assert_eq!(
+            field
+                .flood_fill(Coord2D(150, 150), Neighborhood::VonNeumann, |_, v| !*v)
+                .len(),
+            300 * 300
+        );
+    }
+
+    #[test]
+    fn droplet_exterior() {
+        let mut droplet = ArbGrid::new(false);
+        let cubes = "2,2,2 1,2,2 3,2,2 2,1,2 2,3,2 2,2,1 2,2,3 2,2,4 2,2,6 1,2,5 3,2,5 2,1,5 2,3,5";
+        for cube in cubes.split(' ') {
+            let v: Vec<i32> = cube.split(',').map(|n| n.parse().unwrap()).collect();
+            droplet.insert(Coord3D(v[0], v[1], v[2]), true);
+        }
+        let pockets = droplet.enclosed(Neighborhood::VonNeumann, |_, lava| !*lava);
+        assert_eq!(
+            pockets.into_iter().collect::<Vec<_>>(),
+            vec![Coord3D(2, 2, 5)]
+        );
+
+        let pockets = droplet.enclosed(Neighborhood::VonNeumann, |_, lava| !*lava);
+        let exterior: usize = droplet
+            .iter()
+            .map(|(loc, _)| {
+                droplet
+                    .neighbors(*loc, Neighborhood::VonNeumann, Edges::Unbounded)
+                    .filter(|(xy, lava)| !**lava && !pockets.contains(xy))
+                    .count()
+            })
+            .sum();
+        assert_eq!(exterior, 58);
+
+        // Nothing is enclosed if there's a gap in the wall
+        let ring = ArbGrid::from_str_ogl(Coord2D(0, 0), "###\n#.#\n###", &|c, _xy| Some(c));
+        let inside = ring.enclosed(Neighborhood::VonNeumann, |_, c| *c == '.');
+        assert_eq!(inside.len(), 1);
+        let broken = ArbGrid::from_str_ogl(Coord2D(0, 0), "###\n#..\n###", &|c, _xy| Some(c));
+        assert!(broken
+            .enclosed(Neighborhood::VonNeumann, |_, c| *c == '.')
+            .is_empty());
+    }
+
+    #[test]
+    fn lines() {
+        let pts = |a: (i32, i32), b: (i32, i32)| -> Vec<(i32, i32)> {
+            LinePoints::new(Coord2D(a.0, a.1), Coord2D(b.0, b.1))
+                .map(|c| (c.0, c.1))
+                .collect()
+        };
+        assert_eq!(pts((2, 2), (2, 2)), vec![(2, 2)]);
+        assert_eq!(pts((3, 4), (0, 4)), vec![(3, 4), (2, 4), (1, 4), (0, 4)]);
+        assert_eq!(pts((1, -1), (1, 1)), vec![(1, -1), (1, 0), (1, 1)]);
+        assert_eq!(pts((8, 0), (5, 3)), vec![(8, 0), (7, 1), (6, 2), (5, 3)]);
+        assert_eq!(
+            pts((0, 0), (4, 2)),
+            vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)]
+        );
+        // Either way round covers the same cells, one per step on the long axis
+        for (a, b) in [
+            ((0, 0), (5, 13)),
+            ((0, 0), (4, 2)),
+            ((3, -1), (-4, 6)),
+            ((2, 7), (-5, 3)),
+        ] {
+            let mut back = pts(b, a);
+            back.reverse();
+            assert_eq!(back, pts(a, b));
+        }
+        assert_eq!(pts((0, 0), (5, 13)).len(), 14);
+
+        // The 2021 day 5 example
+        let mut vents: ArbGrid<u32> = ArbGrid::new(0);
+        for (a, b) in [
+            ((0, 9), (5, 9)),
+            ((8, 0), (0, 8)),
+            ((9, 4), (3, 4)),
+            ((2, 2), (2, 1)),
+            ((7, 0), (7, 4)),
+            ((6, 4), (2, 0)),
+            ((0, 9), (2, 9)),
+            ((3, 4), (1, 4)),
+            ((0, 0), (8, 8)),
+            ((5, 5), (8, 2)),
+        ]
+        .iter()
+        {
+            vents.draw_line(Coord2D(a.0, a.1), Coord2D(b.0, b.1), 1, |x, y| x + y);
+        }
+        assert_eq!(vents.iter().filter(|(_, n)| **n >= 2).count(), 12);
+    }
+
+    #[test]
+    fn paths() {
+        assert_eq!(
+            parse_path("R8,U5, L12,UR3"),
+            Some(vec![
+                (Direction::Right, 8),
+                (Direction::Up, 5),
+                (Direction::Left, 12),
+                (Direction::UpRight, 3)
+            ])
+        );
+        assert_eq!(parse_path("R8,X5"), None);
+        assert_eq!(parse_path("R"), None);
+
+        let wire = polyline(Coord2D(0, 0), "R8,U5,L5,D3").unwrap();
+        assert_eq!(wire.len(), 22);
+        assert_eq!(wire[8], Coord2D(8, 0));
+        assert_eq!(wire.last(), Some(&Coord2D(3, 2)));
+
+        // Where two wires cross, from the 2019 day 3 example
+        let mut wires: ArbGrid<u8> = ArbGrid::new(0);
+        wires.draw_path(Coord2D(0, 0), "R8,U5,L5,D3", 1, |a, b| a | b);
+        let end = wires.draw_path(Coord2D(0, 0), "U7,R6,D4,L4", 2, |a, b| a | b);
+        assert_eq!(end, Some(Coord2D(2, 3)));
+        let crossings: Vec<Coord2D> = wires
+            .iter()
+            .filter(|(xy, v)| **v == 3 && **xy != Coord2D(0, 0))
+            .map(|(xy, _)| *xy)
+            .collect();
+        assert_eq!(crossings, vec![Coord2D(3, 3), Coord2D(6, 5)]);
+        assert_eq!(
+            wires.draw_path(Coord2D(0, 0), "bogus", 4, |a, b| a | b),
+            None
+        );
+    }
+
+    fn heading(dx: i32, dy: i32) -> Heading {
+        Heading::new(dx, dy).unwrap()
+    }
+
+    #[test]
+    fn headings() {
+        assert_eq!(heading(-4, 6).offset(), Coord2D(-2, 3));
+        assert_eq!(heading(0, -7).offset(), Coord2D(0, -1));
+        assert_eq!(Heading::new(0, 0), None);
+        // Ordering and equality agree, since everything is reduced
+        assert_eq!(heading(2, 0), heading(1, 0));
+        assert_eq!(heading(2, 0).cmp(&heading(1, 0)), std::cmp::Ordering::Equal);
+        assert_ne!(heading(1, 0).cmp(&heading(1, 1)), std::cmp::Ordering::Equal);
+
+        // Counterclockwise from +x by default
+        let mut all: Vec<Heading> = [
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, -3),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (3, 1),
+        ]
+        .iter()
+        .map(|(x, y)| heading(*x, *y))
+        .collect();
+        all.sort();
+        let expect = [
+            (1, 0),
+            (3, 1),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -3),
+            (0, -1),
+            (1, -1),
+        ];
+        assert_eq!(
+            all,
+            expect
+                .iter()
+                .map(|(x, y)| heading(*x, *y))
+                .collect::<Vec<_>>()
+        );
+
+        // Starting anywhere else just rotates the order
+        let start = heading(-1, 0);
+        all.sort_by(|a, b| a.cmp_from(*b, start));
+        assert_eq!(all[0], start);
+        assert_eq!(all[8], heading(-1, 1));
+        // Close enough to fool floats, but 999/1000 is the steeper one
+        assert_eq!(
+            heading(1000, 999).cmp(&heading(999, 998)),
+            std::cmp::Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn sight() {
+        let between: Vec<Coord2D> = points_between(Coord2D(0, 0), Coord2D(6, -9)).collect();
+        assert_eq!(between, vec![Coord2D(2, -3), Coord2D(4, -6)]);
+        assert_eq!(points_between(Coord2D(0, 0), Coord2D(1, 5)).count(), 0);
+        assert_eq!(points_between(Coord2D(2, 2), Coord2D(2, 2)).count(), 0);
+        assert_eq!(points_between(Coord2D(4, 0), Coord2D(10, 0)).count(), 5);
+
+        let walls =
+            ArbGrid::from_str_ogl(Coord2D(0, 0), "a..#\n.#..\n..b.\n...c", &|c, _xy| Some(c));
+        let blocks = |xy| *walls.get(xy) == '#';
+        assert!(!in_sight(Coord2D(0, 0), Coord2D(2, 2), blocks));
+        assert!(in_sight(Coord2D(0, 0), Coord2D(3, 2), blocks));
+        assert!(in_sight(Coord2D(2, 2), Coord2D(3, 3), blocks));
+
+        let points = vec![
+            Coord2D(3, 0),
+            Coord2D(1, 0),
+            Coord2D(2, 2),
+            Coord2D(0, 5),
+            Coord2D(-2, -2),
+            Coord2D(1, 1),
+            Coord2D(0, 0),
+        ];
+        assert_eq!(
+            visible_from(Coord2D(0, 0), points.clone()),
+            vec![Coord2D(1, 0), Coord2D(1, 1), Coord2D(0, 5), Coord2D(-2, -2)]
+        );
+        let lines = sweep(Coord2D(0, 0), points, heading(0, 1));
+        assert_eq!(lines[0], (heading(0, 1), vec![Coord2D(0, 5)]));
+        assert_eq!(
+            lines[3],
+            (heading(1, 1), vec![Coord2D(1, 1), Coord2D(2, 2)])
+        );
+    }
+
+    #[test]
+    fn hex_walks() {
+        // 2017 day 11 uses flat-topped hexes
+        let flat = HexLayout::FlatTop;
+        for (walk, dist) in [
+            ("ne,ne,ne", 3),
+            ("ne,ne,sw,sw", 0),
+            ("ne,ne,s,s", 2),
+            ("se,sw,se,sw,sw", 3),
+        ]
+        .iter()
+        {
+            let end = flat.walk(Hex(0, 0), walk).unwrap();
+            assert_eq!(end.distance(Hex(0, 0)), *dist, "{}", walk);
+        }
+        assert_eq!(flat.parse_dir("NE"), Some(Hex(1, -1)));
+        assert_eq!(flat.parse_dir("e"), None);
+
+        // 2020 day 24 runs pointy-topped directions together
+        let pointy = HexLayout::PointyTop;
+        assert_eq!(pointy.walk(Hex(0, 0), "nwwswee"), Some(Hex(0, 0)));
+        assert_eq!(pointy.walk(Hex(0, 0), "esew"), Some(Hex(0, 1)));
+        assert_eq!(pointy.parse_walk("eex"), None);
+        assert_eq!(pointy.parse_walk("n"), None);
+
+        let h = Hex::from_cube(2, -3, 1);
+        assert_eq!(h.cube(), (2, -3, 1));
+        for n in h.neighbors().iter() {
+            assert_eq!(n.distance(h), 1);
+        }
+        assert_eq!(Hex(0, 0).surrounding(), Hex(0, 0).orthogonal());
+        assert_eq!(Hex(3, -1).manhattan(Hex(-2, 4)), 5);
+    }
+
+    #[test]
+    fn hex_lobby() {
+        // The 2020 day 24 example, on a plain sparse grid
+        let flips = "sesenwnenenewseeswwswswwnenewsewsw neeenesenwnwwswnenewnwwsewnenwseswesw seswneswswsenwwnwse nwnwneseeswswnenewneswwnewseswneseene swweswneswnenwsewnwneneseenw eesenwseswswnenwswnwnwsewwnwsene sewnenenenesenwsewnenwwwse wenwwweseeeweswwwnwwe wsweesenenewnwwnwsenewsenwwsesesenwne neeswseenwwswnwswswnw nenwswwsewswnenenewsenwsenwnesesenew enewnwewneswsewnwswenweswnenwsenwsw sweneswneswneneenwnewenewwneswswnese swwesenesewenwneswnwwneseswwne enesenwswwswneneswsenwnewswseenwsese wnwnesenesenenwwnenwsewesewsesesew nenewswnwewswnenesenwnesewesw eneswnwswnwsenenwnwnwwseeswneewsenese neswnwewnwnwseenwseesewsenwsweewe wseweeenwnesenwwwswnew";
+        let mut floor: ArbGrid<bool, Hex> = ArbGrid::new(false);
+        for line in flips.split(' ') {
+            let tile = HexLayout::PointyTop.walk(Hex(0, 0), line).unwrap();
+            *floor.get_mut(tile) ^= true;
+        }
+        floor.trim();
+        assert_eq!(floor.iter().count(), 10);
+
+        let mut lobby = Automaton::sparse(floor, Neighborhood::VonNeumann);
+        for _ in 0..10 {
+            lobby.step(|cell| {
+                let black = cell.count(|b| *b);
+                if *cell.value {
+                    black == 1 || black == 2
+                } else {
+                    black == 2
+                }
+            });
+        }
+        assert_eq!(lobby.grid().iter().filter(|(_, b)| **b).count(), 37);
+    }
+
+    #[test]
+    fn hex_paths() {
+        // A wall of hexes with one gap, two steps off the straight line
+        let mut grid: ArbGrid<bool, Hex> = ArbGrid::new(false);
+        for r in -3..=3 {
+            if r != 2 {
+                grid.insert(Hex(1, r), true);
+            }
+        }
+        grid.insert(Hex(-3, 0), false);
+        grid.insert(Hex(4, 0), false);
+        let paths = grid.bfs(Hex(0, 0), Neighborhood::VonNeumann, |_, wall| !*wall);
+        assert_eq!(Hex(0, 0).distance(Hex(3, 0)), 3);
+        assert_eq!(paths.distance(Hex(3, 0)), Some(5));
+        let route = paths.path_to(Hex(3, 0)).unwrap();
+        assert!(route.contains(&Hex(1, 2)));
+        assert!(route.windows(2).all(|w| w[0].distance(w[1]) == 1));
+
+        let astar = grid.astar(
+            Hex(0, 0),
+            Hex(3, 0),
+            Neighborhood::VonNeumann,
+            |_, wall| if *wall { None } else { Some(1) },
+            |h| h.distance(Hex(3, 0)) as usize,
+        );
+        assert_eq!(astar.map(|(cost, _)| cost), Some(5));
+    }
+
+    #[test]
+    fn patterns() {
+        let grid = ArbGrid::from_str_ogl(
+            Coord2D(0, 0),
+            "......\n.#....\n..#...\n.##..#\n....##\n......",
+            &|c, _xy| Some(c),
+        );
+        let exact = ArbGrid::from_str_ogl(Coord2D(0, 0), "#.\n.#\n##", &|c, _xy| Some(c));
+        let found = grid.find_pattern(&exact, |p, g| p == g);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].at, Coord2D(1, 1));
+        assert_eq!(found[0].cells.len(), 6);
+
+        // Only the stored cells count, wherever the pattern was parsed
+        let diagonal = ArbGrid::from_str_ogl(Coord2D(7, 7), "#?\n?#", &|c, _xy| match c {
+            '?' => None,
+            c => Some(c),
+        });
+        assert_eq!(
+            grid.find_pattern(&diagonal, |p, g| p == g),
+            vec![PatternMatch {
+                at: Coord2D(1, 1),
+                cells: vec![Coord2D(1, 1), Coord2D(2, 2)]
+            }]
+        );
+
+        // An L fits a 2x2 box four ways, each of which is also a mirror image
+        // of another, so each spot still only turns up once
+        let ell = ArbGrid::from_str_ogl(Coord2D(0, 0), "#.\n##", &|c, _xy| Some(c));
+        assert_eq!(grid.find_pattern(&ell, |p, g| p == g).len(), 0);
+        let found = grid.find_pattern_any_orientation(&ell, |p, g| p == g);
+        let mut spots: Vec<Coord2D> = found.iter().map(|m| m.at).collect();
+        spots.sort();
+        assert_eq!(spots, vec![Coord2D(1, 2), Coord2D(4, 3)]);
+
+        // Masking out what matched, like the day 20 roughness count
+        let mut rough = grid.clone();
+        for cell in found.iter().flat_map(|m| m.cells.iter()) {
+            rough.insert(*cell, '.');
+        }
+        assert_eq!(rough.iter().filter(|(_, c)| **c == '#').count(), 1);
+
+        assert!(grid
+            .find_pattern(&ArbGrid::new('#'), |p, g| p == g)
+            .is_empty());
+    }
 }
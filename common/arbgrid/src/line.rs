@@ -0,0 +1,118 @@
+use crate::{ArbGrid, Coord2D, Direction};
+use std::fmt::Debug;
+
+/// The cells along a straight segment, both ends included, one per step
+/// along the longer axis. Each is the cell nearest the true line, with ties
+/// going to the higher coordinate, so rows, columns and 45° diagonals come
+/// out exact and a line drawn backwards covers the same cells.
+#[derive(Debug, Clone)]
+pub struct LinePoints {
+    from: Coord2D,
+    delta: Coord2D,
+    steps: i32,
+    next: i32,
+}
+
+impl LinePoints {
+    pub fn new(from: Coord2D, to: Coord2D) -> Self {
+        let delta = to - from;
+        LinePoints {
+            from,
+            delta,
+            steps: delta.0.abs().max(delta.1.abs()),
+            next: 0,
+        }
+    }
+
+    /// `start + i * d / steps`, rounded half up. Rounding the real position
+    /// rather than the offset is what makes it the same from either end.
+    fn along(&self, start: i32, d: i32) -> i32 {
+        if self.steps == 0 {
+            return start;
+        }
+        let (i, d, n) = (self.next as i64, d as i64, self.steps as i64);
+        start + (2 * i * d + n).div_euclid(2 * n) as i32
+    }
+}
+
+impl Iterator for LinePoints {
+    type Item = Coord2D;
+
+    fn next(&mut self) -> Option<Coord2D> {
+        if self.next > self.steps {
+            return None;
+        }
+        let out = Coord2D(
+            self.along(self.from.0, self.delta.0),
+            self.along(self.from.1, self.delta.1),
+        );
+        self.next += 1;
+        Some(out)
+    }
+}
+
+/// Reads a path like `R8,U5,L5` into its moves. Diagonals (`UR3`) work
+/// too, as does anything else `Direction` can parse.
+pub fn parse_path(path: &str) -> Option<Vec<(Direction, u32)>> {
+    path.split(',')
+        .map(|step| {
+            let step = step.trim();
+            let split = step.find(|c: char| c.is_ascii_digit())?;
+            let (dir, dist) = step.split_at(split);
+            Some((dir.parse().ok()?, dist.parse().ok()?))
+        })
+        .collect()
+}
+
+/// Every cell visited walking `path` from `start` (y up), in order and
+/// starting with `start`. Cells crossed twice show up twice.
+pub fn polyline(start: Coord2D, path: &str) -> Option<Vec<Coord2D>> {
+    let mut out = vec![start];
+    let mut here = start;
+    for (dir, dist) in parse_path(path)? {
+        for _ in 0..dist {
+            here = here.step(dir, 1);
+            out.push(here);
+        }
+    }
+    Some(out)
+}
+
+impl<T> ArbGrid<T, Coord2D>
+where
+    T: Clone + Debug + PartialEq,
+{
+    /// Puts `value` on every cell from `from` to `to`, with `combine`
+    /// deciding what to do with what's already there, e.g. adding them up
+    /// to count overlaps
+    pub fn draw_line(
+        &mut self,
+        from: Coord2D,
+        to: Coord2D,
+        value: T,
+        combine: impl Fn(&T, &T) -> T,
+    ) {
+        for loc in LinePoints::new(from, to) {
+            let merged = combine(self.get(loc), &value);
+            self.insert(loc, merged);
+        }
+    }
+
+    /// Like `draw_line`, along a path like `R8,U5,L5`. Each visit counts,
+    /// but corners only get drawn once per turn. Gives where it ended up,
+    /// or `None` (having drawn nothing) if the path doesn't parse.
+    pub fn draw_path(
+        &mut self,
+        start: Coord2D,
+        path: &str,
+        value: T,
+        combine: impl Fn(&T, &T) -> T,
+    ) -> Option<Coord2D> {
+        let cells = polyline(start, path)?;
+        for loc in cells.iter() {
+            let merged = combine(self.get(*loc), &value);
+            self.insert(*loc, merged);
+        }
+        cells.last().copied()
+    }
+}
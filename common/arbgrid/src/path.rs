@@ -0,0 +1,199 @@
+use crate::{ArbGrid, Coord, Edges, Grid, Neighborhood};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, VecDeque};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// Everything a search from one cell found: how far each reachable cell is,
+/// and how to get there.
+#[derive(Debug, Clone)]
+pub struct Paths<C: Coord> {
+    start: C,
+    dist: ArbGrid<Option<usize>, C>,
+    prev: BTreeMap<C, C>,
+}
+
+impl<C: Coord> Paths<C> {
+    pub fn start(&self) -> C {
+        self.start
+    }
+
+    /// Cost to reach `to`, if it can be reached at all
+    pub fn distance(&self, to: C) -> Option<usize> {
+        *self.dist.get(to)
+    }
+
+    /// Cost to reach each cell that was reached, with `None` everywhere else
+    pub fn distance_map(&self) -> &ArbGrid<Option<usize>, C> {
+        &self.dist
+    }
+
+    /// Cells from the start to `to`, both included
+    pub fn path_to(&self, to: C) -> Option<Vec<C>> {
+        self.distance(to)?;
+        let mut path = vec![to];
+        let mut here = to;
+        while let Some(p) = self.prev.get(&here) {
+            path.push(*p);
+            here = *p;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+pub(crate) fn bfs<T, C, G>(
+    grid: &G,
+    start: C,
+    hood: Neighborhood,
+    passable: impl Fn(C, &T) -> bool,
+) -> Paths<C>
+where
+    T: Clone + Debug + PartialEq,
+    C: Coord,
+    G: Grid<T, C>,
+{
+    let mut paths = Paths {
+        start,
+        dist: ArbGrid::new(None),
+        prev: BTreeMap::new(),
+    };
+    paths.dist.insert(start, Some(0));
+    let mut queue = VecDeque::new();
+    queue.push_back((start, 0));
+
+    while let Some((here, dist)) = queue.pop_front() {
+        for (next, val) in grid.neighbors(here, hood, Edges::Clip) {
+            if paths.dist.get(next).is_none() && passable(next, val) {
+                paths.dist.insert(next, Some(dist + 1));
+                paths.prev.insert(next, here);
+                queue.push_back((next, dist + 1));
+            }
+        }
+    }
+    paths
+}
+
+pub(crate) fn dijkstra<T, C, G>(
+    grid: &G,
+    start: C,
+    hood: Neighborhood,
+    cost: impl Fn(C, &T) -> Option<usize>,
+) -> Paths<C>
+where
+    T: Clone + Debug + PartialEq,
+    C: Coord,
+    G: Grid<T, C>,
+{
+    let mut paths = Paths {
+        start,
+        dist: ArbGrid::new(None),
+        prev: BTreeMap::new(),
+    };
+    paths.dist.insert(start, Some(0));
+    let mut queue = BinaryHeap::new();
+    queue.push(Reverse((0, start)));
+
+    while let Some(Reverse((dist, here))) = queue.pop() {
+        if paths.distance(here).is_some_and(|d| d < dist) {
+            continue; // Stale
+        }
+        for (next, val) in grid.neighbors(here, hood, Edges::Clip) {
+            if let Some(step) = cost(next, val) {
+                let new_dist = dist + step;
+                if paths.distance(next).is_none_or(|d| new_dist < d) {
+                    paths.dist.insert(next, Some(new_dist));
+                    paths.prev.insert(next, here);
+                    queue.push(Reverse((new_dist, next)));
+                }
+            }
+        }
+    }
+    paths
+}
+
+pub(crate) fn astar<T, C, G>(
+    grid: &G,
+    start: C,
+    goal: C,
+    hood: Neighborhood,
+    cost: impl Fn(C, &T) -> Option<usize>,
+    heuristic: impl Fn(C) -> usize,
+) -> Option<(usize, Vec<C>)>
+where
+    T: Clone + Debug + PartialEq,
+    C: Coord,
+    G: Grid<T, C>,
+{
+    let mut best: BTreeMap<C, usize> = BTreeMap::new();
+    let mut prev: BTreeMap<C, C> = BTreeMap::new();
+    let mut queue = BinaryHeap::new();
+    best.insert(start, 0);
+    queue.push(Reverse((heuristic(start), 0, start)));
+
+    while let Some(Reverse((_, dist, here))) = queue.pop() {
+        if best.get(&here).is_some_and(|d| *d < dist) {
+            continue; // Stale
+        }
+        if here == goal {
+            let mut path = vec![goal];
+            while let Some(p) = prev.get(path.last().unwrap()) {
+                path.push(*p);
+            }
+            path.reverse();
+            return Some((dist, path));
+        }
+        for (next, val) in grid.neighbors(here, hood, Edges::Clip) {
+            if let Some(step) = cost(next, val) {
+                let new_dist = dist + step;
+                if best.get(&next).is_none_or(|d| new_dist < *d) {
+                    best.insert(next, new_dist);
+                    prev.insert(next, here);
+                    queue.push(Reverse((new_dist + heuristic(next), new_dist, next)));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Dijkstra over any kind of state, for puzzles where where you are isn't
+/// enough and you also need to know (say) which keys you're holding.
+/// `next` gives each neighbor with the cost to get there. Returns the cost
+/// and every state along the way, ending with the first to satisfy `done`.
+pub fn shortest_path<S, N, I, D>(start: S, mut next: N, mut done: D) -> Option<(usize, Vec<S>)>
+where
+    S: Clone + Ord + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, usize)>,
+    D: FnMut(&S) -> bool,
+{
+    let mut best: HashMap<S, usize> = HashMap::new();
+    let mut prev: HashMap<S, S> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    best.insert(start.clone(), 0);
+    queue.push(Reverse((0, start)));
+
+    while let Some(Reverse((cost, state))) = queue.pop() {
+        if best.get(&state).is_some_and(|b| *b < cost) {
+            continue; // Stale
+        }
+        if done(&state) {
+            let mut path = vec![state];
+            while let Some(p) = prev.get(path.last().unwrap()) {
+                path.push(p.clone());
+            }
+            path.reverse();
+            return Some((cost, path));
+        }
+        for (neighbor, step) in next(&state) {
+            let new_cost = cost + step;
+            if best.get(&neighbor).is_none_or(|b| new_cost < *b) {
+                best.insert(neighbor.clone(), new_cost);
+                prev.insert(neighbor.clone(), state.clone());
+                queue.push(Reverse((new_cost, neighbor)));
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,77 @@
+use crate::{ArbGrid, Coord2D};
+use std::fmt::Debug;
+
+/// One place a pattern turned up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternMatch {
+    /// Where the pattern's lowest corner (`bounds().0`) landed
+    pub at: Coord2D,
+    /// Every grid cell a (non-wildcard) pattern cell landed on
+    pub cells: Vec<Coord2D>,
+}
+
+impl<T> ArbGrid<T, Coord2D>
+where
+    T: Clone + Debug + PartialEq,
+{
+    /// Every spot within the bounds where `pattern` fits, with `matcher`
+    /// comparing each pattern cell to the grid cell under it. Only cells
+    /// stored in `pattern` get checked, so anything left out of it (e.g.
+    /// by giving `None` to `from_str`) is a wildcard.
+    ///
+    /// Checks every spot, giving up on each at the first cell that doesn't
+    /// match, so it's O(grid * pattern) at worst.
+    pub fn find_pattern<P>(
+        &self,
+        pattern: &ArbGrid<P>,
+        matcher: impl Fn(&P, &T) -> bool,
+    ) -> Vec<PatternMatch>
+    where
+        P: Clone + Debug + PartialEq,
+    {
+        let mut out = Vec::new();
+        let ((plo, phi), (lo, hi)) = match (pattern.bounds(), self.bounds()) {
+            (Some(p), Some(g)) => (p, g),
+            _ => return out,
+        };
+        let cells: Vec<(Coord2D, &P)> = pattern.iter().map(|(xy, p)| (*xy - plo, p)).collect();
+        let span = phi - plo;
+
+        for y in lo.1..=(hi.1 - span.1) {
+            for x in lo.0..=(hi.0 - span.0) {
+                let at = Coord2D(x, y);
+                if cells.iter().all(|(d, p)| matcher(p, self.get(at + *d))) {
+                    out.push(PatternMatch {
+                        at,
+                        cells: cells.iter().map(|(d, _)| at + *d).collect(),
+                    });
+                }
+            }
+        }
+        out
+    }
+
+    /// `find_pattern` for each of the pattern's `orientations`. A symmetric
+    /// pattern only gets searched for once per distinct orientation, so
+    /// the same cells don't show up twice for it.
+    pub fn find_pattern_any_orientation<P>(
+        &self,
+        pattern: &ArbGrid<P>,
+        matcher: impl Fn(&P, &T) -> bool,
+    ) -> Vec<PatternMatch>
+    where
+        P: Clone + Debug + PartialEq,
+    {
+        let mut seen: Vec<ArbGrid<P>> = Vec::new();
+        let mut out = Vec::new();
+        for turned in pattern.orientations() {
+            // Compare what's stored, since wildcards matter here
+            if seen.iter().any(|s| s.iter().eq(turned.iter())) {
+                continue;
+            }
+            out.extend(self.find_pattern(&turned, &matcher));
+            seen.push(turned);
+        }
+        out
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{ArbGrid, Coord, Grid, Neighborhood};
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+
+/// One connected patch of cells
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region<C> {
+    pub size: usize,
+    /// Smallest box holding every cell, inclusive, like `ArbGrid::bounds`
+    pub bounds: (C, C),
+}
+
+/// Every connected patch of matching cells within a grid's bounds, numbered
+/// in the order they were found (which is the order `C` sorts in)
+#[derive(Debug, Clone)]
+pub struct Components<C: Coord> {
+    labels: ArbGrid<Option<usize>, C>,
+    regions: Vec<Region<C>>,
+}
+
+impl<C: Coord> Components<C> {
+    /// Which region `loc` is part of, if any
+    pub fn label(&self, loc: C) -> Option<usize> {
+        *self.labels.get(loc)
+    }
+
+    pub fn regions(&self) -> &[Region<C>] {
+        &self.regions
+    }
+
+    /// The cells in region `label`
+    pub fn cells(&self, label: usize) -> impl Iterator<Item = C> + '_ {
+        self.labels
+            .iter()
+            .filter(move |(_, l)| **l == Some(label))
+            .map(|(loc, _)| *loc)
+    }
+
+    /// Region sizes, biggest first
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = self.regions.iter().map(|r| r.size).collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+}
+
+/// Everything reachable from `start` through cells where `inside` holds,
+/// without leaving `within`. Uses its own stack, so big regions are fine.
+fn fill_from<C: Coord>(
+    start: C,
+    hood: Neighborhood,
+    within: (C, C),
+    inside: impl Fn(C) -> bool,
+    seen: &mut BTreeSet<C>,
+) -> Vec<C> {
+    let dirs: Vec<C> = hood.directions();
+    let mut found = Vec::new();
+    if !start.within(within.0, within.1) || !inside(start) || !seen.insert(start) {
+        return found;
+    }
+    let mut stack = vec![start];
+    while let Some(here) = stack.pop() {
+        found.push(here);
+        for dir in dirs.iter() {
+            let next = here.offset(*dir);
+            if next.within(within.0, within.1) && inside(next) && seen.insert(next) {
+                stack.push(next);
+            }
+        }
+    }
+    found
+}
+
+pub(crate) fn flood_fill<T, C, G>(
+    grid: &G,
+    start: C,
+    hood: Neighborhood,
+    inside: impl Fn(C, &T) -> bool,
+) -> BTreeSet<C>
+where
+    T: Clone + Debug + PartialEq,
+    C: Coord,
+    G: Grid<T, C>,
+{
+    let mut seen = BTreeSet::new();
+    if let Some(bounds) = grid.bounds() {
+        fill_from(
+            start,
+            hood,
+            bounds,
+            |loc| inside(loc, grid.get(loc)),
+            &mut seen,
+        );
+    }
+    seen
+}
+
+pub(crate) fn components<T, C, G>(
+    grid: &G,
+    hood: Neighborhood,
+    inside: impl Fn(C, &T) -> bool,
+) -> Components<C>
+where
+    T: Clone + Debug + PartialEq,
+    C: Coord,
+    G: Grid<T, C>,
+{
+    let mut out = Components {
+        labels: ArbGrid::new(None),
+        regions: Vec::new(),
+    };
+    let bounds = match grid.bounds() {
+        Some(b) => b,
+        None => return out,
+    };
+    let mut seen = BTreeSet::new();
+    for loc in C::box_iter(bounds.0, bounds.1) {
+        let cells = fill_from(loc, hood, bounds, |c| inside(c, grid.get(c)), &mut seen);
+        if cells.is_empty() {
+            continue;
+        }
+        let label = out.regions.len();
+        let mut lo = cells[0];
+        let mut hi = cells[0];
+        for c in cells.iter() {
+            lo = lo.bound_min(*c);
+            hi = hi.bound_max(*c);
+            out.labels.insert(*c, Some(label));
+        }
+        out.regions.push(Region {
+            size: cells.len(),
+            bounds: (lo, hi),
+        });
+    }
+    out
+}
+
+pub(crate) fn enclosed<T, C, G>(
+    grid: &G,
+    hood: Neighborhood,
+    open: impl Fn(C, &T) -> bool,
+) -> BTreeSet<C>
+where
+    T: Clone + Debug + PartialEq,
+    C: Coord,
+    G: Grid<T, C>,
+{
+    let (lo, hi) = match grid.bounds() {
+        Some(b) => b,
+        None => return BTreeSet::new(),
+    };
+    // One layer of open space all the way around, so the outside is a
+    // single region no matter what touches the edges
+    let pad_lo = C::from_axes(|i| lo.axis(i) - 1);
+    let pad_hi = C::from_axes(|i| hi.axis(i) + 1);
+    let is_open = |c: C| !c.within(lo, hi) || open(c, grid.get(c));
+
+    let mut outside = BTreeSet::new();
+    fill_from(pad_lo, hood, (pad_lo, pad_hi), is_open, &mut outside);
+    C::box_iter(lo, hi)
+        .into_iter()
+        .filter(|c| is_open(*c) && !outside.contains(c))
+        .collect()
+}
@@ -0,0 +1,141 @@
+use crate::Coord2D;
+use aocmath::gcd;
+use std::cmp::Ordering;
+
+/// Which way one point is from another, reduced so that everything along
+/// the same line of sight has the same heading. Angles are compared with
+/// cross products, so there's no floating point to tie on.
+///
+/// `Ord` goes counterclockwise from +x, like `atan2` on a y-up grid. On a
+/// y-down grid (`from_str_ogl`) that looks clockwise on screen.
+///
+/// The only way to get one is through `new` or `between`, so it's always
+/// reduced and never zero. Otherwise `(2, 0)` and `(1, 0)` would sort as
+/// equal without being `==`.
+/// ```compile_fail
+/// let unreduced = arbgrid::Heading(2, 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Heading(i32, i32);
+
+impl Heading {
+    /// Reduces any non-zero offset down to its heading
+    pub fn new(dx: i32, dy: i32) -> Option<Heading> {
+        Heading::between(Coord2D(0, 0), Coord2D(dx, dy)).map(|(h, _)| h)
+    }
+
+    /// Heading from `from` to `to`, plus how many steps of it that takes.
+    /// ```
+    /// use arbgrid::{Coord2D, Heading};
+    /// let (heading, steps) = Heading::between(Coord2D(1, 1), Coord2D(7, -3)).unwrap();
+    /// assert_eq!((heading.dx(), heading.dy(), steps), (3, -2, 2));
+    /// assert_eq!(Heading::between(Coord2D(1, 1), Coord2D(1, 1)), None);
+    /// ```
+    pub fn between(from: Coord2D, to: Coord2D) -> Option<(Heading, i32)> {
+        let dx = to.0 - from.0;
+        let dy = to.1 - from.1;
+        let steps = gcd(dx.abs(), dy.abs());
+        if steps == 0 {
+            return None;
+        }
+        Some((Heading(dx / steps, dy / steps), steps))
+    }
+
+    pub fn dx(self) -> i32 {
+        self.0
+    }
+
+    pub fn dy(self) -> i32 {
+        self.1
+    }
+
+    /// One step this way
+    pub fn offset(self) -> Coord2D {
+        Coord2D(self.0, self.1)
+    }
+
+    fn cross(self, other: Heading) -> i64 {
+        self.0 as i64 * other.1 as i64 - self.1 as i64 * other.0 as i64
+    }
+
+    fn dot(self, other: Heading) -> i64 {
+        self.0 as i64 * other.0 as i64 + self.1 as i64 * other.1 as i64
+    }
+
+    /// 0 for anything from `reference` up to just short of the opposite way,
+    /// going counterclockwise, and 1 for the rest
+    fn half(self, reference: Heading) -> u8 {
+        let cross = reference.cross(self);
+        if cross > 0 || (cross == 0 && reference.dot(self) > 0) {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Orders by how far counterclockwise each is from `reference`, which
+    /// itself comes first
+    pub fn cmp_from(self, other: Heading, reference: Heading) -> Ordering {
+        self.half(reference)
+            .cmp(&other.half(reference))
+            .then_with(|| 0.cmp(&self.cross(other)))
+    }
+}
+
+impl Ord for Heading {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_from(*other, Heading(1, 0))
+    }
+}
+
+impl PartialOrd for Heading {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The whole-numbered points strictly between `from` and `to`
+pub fn points_between(from: Coord2D, to: Coord2D) -> impl Iterator<Item = Coord2D> {
+    let (step, steps) = Heading::between(from, to)
+        .map(|(h, steps)| (h.offset(), steps))
+        .unwrap_or((Coord2D(0, 0), 0));
+    (1..steps).map(move |m| from + step * m)
+}
+
+/// Whether nothing between `from` and `to` (ends not included) `blocks`
+pub fn in_sight(from: Coord2D, to: Coord2D, blocks: impl Fn(Coord2D) -> bool) -> bool {
+    points_between(from, to).all(|xy| !blocks(xy))
+}
+
+/// Groups `points` by their heading from `from`, nearest first, with the
+/// headings going counterclockwise starting at `start`. Points at `from`
+/// itself are left out. Sorting is the slow part, so it's O(n log n).
+pub fn sweep(
+    from: Coord2D,
+    points: impl IntoIterator<Item = Coord2D>,
+    start: Heading,
+) -> Vec<(Heading, Vec<Coord2D>)> {
+    let mut all: Vec<(Heading, i32, Coord2D)> = points
+        .into_iter()
+        .filter_map(|xy| Heading::between(from, xy).map(|(h, steps)| (h, steps, xy)))
+        .collect();
+    all.sort_by(|a, b| a.0.cmp_from(b.0, start).then(a.1.cmp(&b.1)));
+
+    let mut out: Vec<(Heading, Vec<Coord2D>)> = Vec::new();
+    for (heading, _, xy) in all {
+        match out.last_mut() {
+            Some((last, line)) if *last == heading => line.push(xy),
+            _ => out.push((heading, vec![xy])),
+        }
+    }
+    out
+}
+
+/// Which of `points` can be seen from `from` when each one blocks the view
+/// of anything behind it, in `Heading` order
+pub fn visible_from(from: Coord2D, points: impl IntoIterator<Item = Coord2D>) -> Vec<Coord2D> {
+    sweep(from, points, Heading(1, 0))
+        .into_iter()
+        .map(|(_, line)| line[0])
+        .collect()
+}
@@ -0,0 +1,234 @@
+use crate::{ArbGrid, Coord2D, Orientation, Rgb};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How one cell looks on the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Style {
+    pub ch: char,
+    pub fg: Option<Rgb>,
+    pub bg: Option<Rgb>,
+}
+
+impl Style {
+    pub fn plain(ch: char) -> Self {
+        Style {
+            ch,
+            fg: None,
+            bg: None,
+        }
+    }
+
+    pub fn fg(self, fg: Rgb) -> Self {
+        Style {
+            fg: Some(fg),
+            ..self
+        }
+    }
+
+    pub fn bg(self, bg: Rgb) -> Self {
+        Style {
+            bg: Some(bg),
+            ..self
+        }
+    }
+
+    fn write_to(self, out: &mut impl Write) -> io::Result<()> {
+        if let Some([r, g, b]) = self.fg {
+            write!(out, "\x1b[38;2;{};{};{}m", r, g, b)?;
+        }
+        if let Some([r, g, b]) = self.bg {
+            write!(out, "\x1b[48;2;{};{};{}m", r, g, b)?;
+        }
+        write!(out, "{}", self.ch)?;
+        if self.fg.is_some() || self.bg.is_some() {
+            write!(out, "\x1b[0m")?;
+        }
+        Ok(())
+    }
+}
+
+/// Draws a grid over and over in the same spot of a terminal, only
+/// rewriting the cells that changed since the last frame. If the bounds
+/// change, everything moves, so that frame gets drawn from scratch.
+pub struct Renderer<W: Write> {
+    out: W,
+    orientation: Orientation,
+    frame_time: Option<Duration>,
+    last_frame: Option<Instant>,
+    bounds: Option<(Coord2D, Coord2D)>,
+    /// What's on screen now, by (row, column)
+    shown: BTreeMap<(usize, usize), Style>,
+    rows: usize,
+}
+
+impl<W: Write> Renderer<W> {
+    /// Draws y-up, like `to_string`, as fast as frames come in
+    pub fn new(out: W) -> Self {
+        Renderer {
+            out,
+            orientation: Orientation::YUp,
+            frame_time: None,
+            last_frame: None,
+            bounds: None,
+            shown: BTreeMap::new(),
+            rows: 0,
+        }
+    }
+
+    pub fn orientation(self, orientation: Orientation) -> Self {
+        Renderer {
+            orientation,
+            ..self
+        }
+    }
+
+    /// Waits between frames so there are at most `fps` a second
+    pub fn max_fps(self, fps: u32) -> Self {
+        Renderer {
+            frame_time: Some(Duration::from_secs(1) / fps.max(1)),
+            ..self
+        }
+    }
+
+    pub fn draw<T>(
+        &mut self,
+        grid: &ArbGrid<T>,
+        style_fn: &dyn Fn(&T, Coord2D) -> Style,
+    ) -> io::Result<()>
+    where
+        T: Clone + Debug + PartialEq,
+    {
+        if let (Some(frame_time), Some(last)) = (self.frame_time, self.last_frame) {
+            let spent = last.elapsed();
+            if spent < frame_time {
+                thread::sleep(frame_time - spent);
+            }
+        }
+        self.last_frame = Some(Instant::now());
+
+        let bounds = grid.bounds();
+        if bounds != self.bounds {
+            // Home and clear
+            write!(self.out, "\x1b[H\x1b[2J")?;
+            self.shown.clear();
+            self.bounds = bounds;
+        }
+        let (lo, hi) = match bounds {
+            Some(b) => b,
+            None => {
+                self.rows = 0;
+                return self.out.flush();
+            }
+        };
+        self.rows = (hi.1 - lo.1 + 1) as usize;
+
+        for row in 0..self.rows {
+            let y = match self.orientation {
+                Orientation::YUp => hi.1 - row as i32,
+                Orientation::YDown => lo.1 + row as i32,
+            };
+            let mut cursor_here = false;
+            for x in lo.0..=hi.0 {
+                let col = (x - lo.0) as usize;
+                let loc = Coord2D(x, y);
+                let style = style_fn(grid.get(loc), loc);
+                if self.shown.get(&(row, col)) == Some(&style) {
+                    cursor_here = false;
+                    continue;
+                }
+                if !cursor_here {
+                    // Terminal rows and columns count from 1
+                    write!(self.out, "\x1b[{};{}H", row + 1, col + 1)?;
+                }
+                style.write_to(&mut self.out)?;
+                self.shown.insert((row, col), style);
+                cursor_here = true;
+            }
+        }
+        self.out.flush()
+    }
+
+    /// Leaves the cursor under the last frame and gives back the output
+    pub fn finish(mut self) -> io::Result<W> {
+        write!(self.out, "\x1b[{};1H", self.rows + 1)?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// Records everything written to it as an asciicast (v2) recording, one
+/// event per `flush`. `Renderer` flushes once a frame, so every frame plays
+/// back `frame_time` after the one before, however long it took to make.
+pub struct Asciicast<W: Write> {
+    out: W,
+    frame_time: Duration,
+    frames: u32,
+    pending: Vec<u8>,
+}
+
+impl<W: Write> Asciicast<W> {
+    /// Writes the header for a terminal of `width` by `height` right away
+    pub fn new(mut out: W, width: usize, height: usize, frame_time: Duration) -> io::Result<Self> {
+        writeln!(
+            out,
+            r#"{{"version": 2, "width": {}, "height": {}}}"#,
+            width, height
+        )?;
+        Ok(Asciicast {
+            out,
+            frame_time,
+            frames: 0,
+            pending: Vec::new(),
+        })
+    }
+
+    pub fn into_inner(mut self) -> io::Result<W> {
+        if !self.pending.is_empty() {
+            self.flush()?;
+        }
+        Ok(self.out)
+    }
+}
+
+fn json_string(bytes: &[u8]) -> String {
+    let mut out = String::from("\"");
+    for c in String::from_utf8_lossy(bytes).chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl<W: Write> Write for Asciicast<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    /// Frames where nothing changed still take up time, but don't need an event
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.pending.is_empty() {
+            let time = (self.frame_time * self.frames).as_secs_f64();
+            writeln!(
+                self.out,
+                "[{:.6}, \"o\", {}]",
+                time,
+                json_string(&self.pending)
+            )?;
+            self.pending.clear();
+        }
+        self.frames += 1;
+        self.out.flush()
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{ArbGrid, Coord, Coord2D};
+use std::fmt::Debug;
+
+/// Rotations and flips work on the bounding box, and keep its bottom-left
+/// corner where it was. Directions are as drawn by `to_string`, with y going
+/// up; a grid drawn with `to_string_ogl` turns the other way.
+impl<T> ArbGrid<T, Coord2D>
+where
+    T: Clone + Debug + PartialEq,
+{
+    /// Moves every stored cell with `f`, which takes and gives coordinates
+    /// relative to the bottom-left corner, along with the top-right one
+    fn remap(&self, f: impl Fn(Coord2D, Coord2D) -> Coord2D) -> Self {
+        let (lo, hi) = match self.bounds() {
+            Some(b) => b,
+            None => return self.clone(),
+        };
+        let size = Coord2D(hi.0 - lo.0, hi.1 - lo.1);
+        let mut out = ArbGrid::new(self.default.clone());
+        for (loc, v) in self.iter() {
+            let moved = f(Coord2D(loc.0 - lo.0, loc.1 - lo.1), size);
+            out.insert(lo.offset(moved), v.clone());
+        }
+        out
+    }
+
+    pub fn rotate_cw(&self) -> Self {
+        self.remap(|Coord2D(x, y), Coord2D(w, _h)| Coord2D(y, w - x))
+    }
+
+    pub fn rotate_ccw(&self) -> Self {
+        self.remap(|Coord2D(x, y), Coord2D(_w, h)| Coord2D(h - y, x))
+    }
+
+    pub fn rotate_180(&self) -> Self {
+        self.remap(|Coord2D(x, y), Coord2D(w, h)| Coord2D(w - x, h - y))
+    }
+
+    /// Mirrors left to right
+    pub fn flip_horizontal(&self) -> Self {
+        self.remap(|Coord2D(x, y), Coord2D(w, _h)| Coord2D(w - x, y))
+    }
+
+    /// Mirrors top to bottom
+    pub fn flip_vertical(&self) -> Self {
+        self.remap(|Coord2D(x, y), Coord2D(_w, h)| Coord2D(x, h - y))
+    }
+
+    /// Swaps x and y
+    pub fn transpose(&self) -> Self {
+        self.remap(|Coord2D(x, y), _size| Coord2D(y, x))
+    }
+
+    /// All eight ways to rotate and flip the grid: the four rotations
+    /// (starting with the grid as-is, then turning clockwise), followed by
+    /// the same four mirrored
+    pub fn orientations(&self) -> Vec<Self> {
+        let mut out = vec![self.clone()];
+        for i in 0..3 {
+            out.push(out[i].rotate_cw());
+        }
+        for i in 0..4 {
+            out.push(out[i].flip_horizontal());
+        }
+        out
+    }
+}
+
+impl<T, C> ArbGrid<T, C>
+where
+    T: Clone + Debug + PartialEq,
+    C: Coord,
+{
+    /// Just the cells between `lo` and `hi`, inclusive, at the same
+    /// coordinates they had here
+    pub fn crop(&self, lo: C, hi: C) -> Self {
+        let mut out = ArbGrid::new(self.default.clone());
+        for (loc, v) in self.iter().filter(|(loc, _)| loc.within(lo, hi)) {
+            out.insert(*loc, v.clone());
+        }
+        out
+    }
+
+    /// Copies every cell of `other` here, moved by `offset`
+    pub fn paste(&mut self, other: &Self, offset: C) {
+        self.paste_with(other, offset, |_old, new| new.clone());
+    }
+
+    /// Like `paste`, but `combine` decides what to do with the cell that's
+    /// already here and the one landing on it
+    pub fn paste_with(&mut self, other: &Self, offset: C, combine: impl Fn(&T, &T) -> T) {
+        for (loc, v) in other.iter() {
+            let loc = loc.offset(offset);
+            let merged = combine(self.get(loc), v);
+            self.insert(loc, merged);
+        }
+    }
+}
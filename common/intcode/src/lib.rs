@@ -58,6 +58,7 @@ pub enum RunMode {
     InputStalled,
 }
 
+#[derive(Clone)]
 pub struct IntMachine {
     tape: Vec<Atom>,
     pc: usize,
@@ -195,6 +196,18 @@ impl IntMachine {
         self.run_mode
     }
 
+    /// Like `run`, but gives up after `max_steps` instructions. A result of
+    /// `RunMode::Running` means the budget ran out before the program stopped.
+    pub fn run_for(&mut self, max_steps: usize) -> RunMode {
+        for _ in 0..max_steps {
+            if self.run_mode != RunMode::Running {
+                break;
+            }
+            self.step();
+        }
+        self.run_mode
+    }
+
     pub fn get_tape(&self) -> &[Atom] {
         &self.tape
     }
@@ -406,6 +419,23 @@ mod tests {
         check(9, 1001);
     }
 
+    #[test]
+    fn budgeted_run() {
+        // Spins forever: jump-if-true 1 back to 0
+        let mut cpu = IntMachine::new(vec![1105, 1, 0]);
+        assert_eq!(cpu.run_for(1000), RunMode::Running);
+
+        let mut cpu = IntMachine::new(vec![3, 0, 4, 0, 99]);
+        assert_eq!(cpu.run_for(1000), RunMode::InputStalled);
+        let mut snapshot = cpu.clone();
+        cpu.feed_one(7);
+        snapshot.feed_one(8);
+        assert_eq!(cpu.run_for(1000), RunMode::EndPgm);
+        assert_eq!(snapshot.run_for(1000), RunMode::EndPgm);
+        assert_eq!(cpu.output, vec!(7));
+        assert_eq!(snapshot.output, vec!(8));
+    }
+
     #[test]
     fn it_works() {
         assert_eq!(2 + 2, 4);